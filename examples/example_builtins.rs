@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let packet = Packet {
+        header: [0xca, 0xfe],
+        payload: b"hello".to_vec(),
+    };
+    checksum(&packet.payload, 0x1f);
+    describe("a rather long description that is not worth logging in full");
+    fingerprint(&packet);
+    same(&packet, &packet);
+
+    let mut sessions = Sessions::default();
+    sessions.open("alice", vec![1, 2, 3]);
+    sessions.open("bob", vec![4; 100]);
+}
+
+#[derive(Debug)]
+struct Packet {
+    header: [u8; 2],
+    payload: Vec<u8>,
+}
+
+#[trace(bytes = hex, seed = hex, res = hex)]
+fn checksum(bytes: &[u8], seed: u32) -> u32 {
+    bytes
+        .iter()
+        .fold(seed, |sum, &byte| sum.rotate_left(5) ^ u32::from(byte))
+}
+
+#[trace(text = truncate(16), res = len)]
+fn describe(text: &str) -> String {
+    text.to_uppercase()
+}
+
+#[trace(packet = hash)]
+fn fingerprint(packet: &Packet) -> usize {
+    packet.header.len() + packet.payload.len()
+}
+
+#[trace(a = addr, b = addr)]
+fn same(a: &Packet, b: &Packet) -> bool {
+    std::ptr::eq(a, b)
+}
+
+#[derive(Default)]
+struct Sessions {
+    data: HashMap<String, Vec<u8>>,
+}
+
+// Every value is cut after 24 bytes, but the names, which are short.
+#[trace(default = truncate(24), name = "{}")]
+impl Sessions {
+    fn open(&mut self, name: &str, data: Vec<u8>) -> usize {
+        self.data.insert(name.to_string(), data);
+        self.data.len()
+    }
+}
@@ -11,6 +11,8 @@ async fn main() {
 
     let _ = loop_early_exit(1000);
     // let _ = loop_early_exit_expected(1000);
+
+    exit(0);
 }
 
 #[trace]
@@ -100,3 +102,11 @@ fn loop_early_exit(a: u32) -> u32 {
 //
 //     __inner_return_value__
 // }
+
+//==================================================================================================
+
+// A diverging function is traced when entered only.
+#[trace]
+fn exit(code: i32) -> ! {
+    std::process::exit(code)
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let connection = Connection {
+        address: "localhost".to_string(),
+        retries: 3,
+    };
+    query(&connection, "SELECT 1");
+    let _ = open(vec![connection]);
+    db::wrap(42);
+    wrap(42);
+}
+
+// `Connection` does not implement `Debug`.
+struct Connection {
+    address: String,
+    retries: u32,
+}
+
+#[trace]
+mod db {
+    use super::*;
+
+    pub fn query(connection: &Connection, sql: &str) -> usize {
+        connection.address.len() + sql.len() + connection.retries as usize
+    }
+
+    pub fn open(connections: Vec<Connection>) -> HashMap<String, Connection> {
+        connections
+            .into_iter()
+            .map(|connection| (connection.address.clone(), connection))
+            .collect()
+    }
+
+    // Whether `T` implements `Debug` is decided for each call.
+    pub fn wrap<T: std::fmt::Debug>(value: T) -> Option<T> {
+        Some(value)
+    }
+}
+
+use db::{open, query};
+
+// Without the bound, the values are logged by their types even if they implement `Debug`.
+#[trace]
+fn wrap<T>(value: T) -> Vec<T> {
+    vec![value]
+}
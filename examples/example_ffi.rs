@@ -0,0 +1,34 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let text = CString::new("hello").unwrap();
+
+    unsafe {
+        libc::abs(-3);
+        libc::strlen(text.as_ptr());
+        libc::atoi(text.as_ptr());
+    }
+}
+
+#[trace(prefix = "libc::")]
+mod libc {
+    use super::*;
+
+    extern "C" {
+        pub fn abs(x: c_int) -> c_int;
+
+        // Pointers are printed as addresses.
+        pub fn strlen(s: *const c_char) -> usize;
+
+        #[trace(disable(res))]
+        pub fn atoi(s: *const c_char) -> c_int;
+
+        // Variadic functions are left as they are.
+        #[allow(dead_code)]
+        pub fn printf(format: *const c_char, ...) -> c_int;
+    }
+}
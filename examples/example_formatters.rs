@@ -0,0 +1,49 @@
+use std::fmt;
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    toggle(0xdead_0000, 0xbeef);
+
+    let mut buffer = Buffer {
+        id: 7,
+        data: Vec::new(),
+    };
+    buffer.extend(&[1, 2, 3]);
+    buffer.contents();
+
+    let mut buf = vec![0; 4];
+    fill(&mut buf, 1);
+}
+
+fn hex(value: &u32, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:#010x}", value)
+}
+
+#[trace(flags = with(hex), res = with(hex))]
+fn toggle(flags: u32, mask: u32) -> u32 {
+    flags ^ mask
+}
+
+struct Buffer {
+    id: u32,
+    data: Vec<u8>,
+}
+
+impl Buffer {
+    #[trace(self = expr(self.id), data = expr(data.len()))]
+    fn extend(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    #[trace(res = expr(res.len()))]
+    fn contents(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+#[trace(buf = expr(buf.len()), mut_after)]
+fn fill(buf: &mut Vec<u8>, byte: u8) {
+    buf.resize(buf.len() * 2 + 1, byte);
+}
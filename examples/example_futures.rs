@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use std::future::Future;
+use std::time::Duration;
+use trace::trace;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let store = Store;
+
+    // The exit is reported once the future completes.
+    let value = fetch(1);
+    tokio::time::delay_for(Duration::from_millis(10)).await;
+    value.await;
+    Box::into_pin(fetch_boxed(2)).await;
+
+    store.load(2).await;
+    store.save(3).await;
+
+    let cache = Cache;
+    cache.load(4).await;
+    cache.save(5).await;
+}
+
+#[trace]
+fn fetch(id: u8) -> impl Future<Output = u8> {
+    // The delay starts when the future is created rather than when it is first polled.
+    let delay = tokio::time::delay_for(Duration::from_millis(10));
+    async move {
+        delay.await;
+        id * 2
+    }
+}
+
+#[trace]
+fn fetch_boxed(id: u8) -> Box<dyn Future<Output = u8> + Send> {
+    Box::new(fetch(id))
+}
+
+#[async_trait]
+trait Storage {
+    async fn load(&self, id: u8) -> u8;
+    async fn save(&self, id: u8);
+}
+
+struct Store;
+
+// Either order of the attributes works: `#[trace]` below `#[async_trait]` traces the expanded methods,
+// which return boxed futures, `#[trace]` above it, as on `Cache`, traces the `async fn`s.
+#[async_trait]
+#[trace(prefix = "Store::")]
+impl Storage for Store {
+    async fn load(&self, id: u8) -> u8 {
+        fetch(id).await + 1
+    }
+
+    async fn save(&self, id: u8) {
+        tokio::time::delay_for(Duration::from_millis(id.into())).await;
+    }
+}
+
+struct Cache;
+
+#[trace(prefix = "Cache::")]
+#[async_trait]
+impl Storage for Cache {
+    async fn load(&self, id: u8) -> u8 {
+        id
+    }
+
+    async fn save(&self, id: u8) {
+        tokio::time::delay_for(Duration::from_millis(id.into())).await;
+    }
+}
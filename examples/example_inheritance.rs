@@ -0,0 +1,38 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let f = Foo::new();
+    f.bar(1, 2);
+    f.baz(3);
+    f.qux(4);
+}
+
+struct Foo;
+
+#[trace(pretty, prefix = "Foo::", a = "a = {}")]
+impl Foo {
+    #[trace(off)]
+    fn new() -> Self {
+        Self
+    }
+
+    // Inherits `pretty`, the prefix and the format of `a`.
+    #[trace(b = "b = {}")]
+    fn bar(&self, a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    // Formats of the enclosing `impl` are overridden.
+    #[trace(a = "overridden {}")]
+    fn baz(&self, a: i32) -> i32 {
+        a
+    }
+
+    // Nothing is inherited from the enclosing `impl`.
+    #[trace(inherit = false)]
+    fn qux(&self, a: i32) -> i32 {
+        a
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let user = users::User::new(1, "Alice");
+    let _ = user.id();
+    let _ = user.rename("Bob");
+    let _ = format!("{:?} {}", user, user);
+    let _ = user.clone();
+    users::helper();
+}
+
+#[trace(
+    only_pub,
+    skip_trivial,
+    skip_trait_impls(Debug, Clone),
+    disable("impl Display for *")
+)]
+mod users {
+    use super::fmt;
+
+    pub struct User {
+        id: u32,
+        name: String,
+    }
+
+    impl User {
+        pub fn new(id: u32, name: &str) -> Self {
+            Self {
+                id,
+                name: name.to_string(),
+            }
+        }
+
+        // Skipped, a trivial getter.
+        pub fn id(&self) -> u32 {
+            self.id
+        }
+
+        pub fn rename(&self, name: &str) -> Self {
+            Self::new(self.id, &format!("{} ({})", name, private_suffix()))
+        }
+    }
+
+    // Skipped, not public.
+    fn private_suffix() -> &'static str {
+        "renamed"
+    }
+
+    pub fn helper() {}
+
+    // Skipped by `skip_trait_impls`.
+    impl fmt::Debug for User {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "User({}, {})", self.id, self.name)
+        }
+    }
+
+    // Skipped by `disable`.
+    impl fmt::Display for User {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.name)
+        }
+    }
+
+    impl Clone for User {
+        fn clone(&self) -> Self {
+            Self::new(self.id, &self.name)
+        }
+    }
+}
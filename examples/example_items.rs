@@ -0,0 +1,55 @@
+use std::iter::FusedIterator;
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    // Every item is logged.
+    let total: u32 = squares(4).sum();
+    println!("total: {}", total);
+
+    // Only the first three items are logged, then the iterator is dropped before its end.
+    let evens = Numbers { step: 2 }.take_up_to(100).take(5).count();
+    println!("evens: {}", evens);
+
+    let words = split("a quick brown fox").collect::<Vec<_>>();
+    println!("words: {:?}", words);
+
+    // The traced iterator keeps the bounds of the returned one, each clone is traced on its own.
+    let doubles = doubles(4);
+    println!("len: {}", doubles.len());
+    let backwards = doubles.clone().rev().collect::<Vec<_>>();
+    println!(
+        "doubles: {:?}, backwards: {:?}",
+        doubles.collect::<Vec<_>>(),
+        backwards
+    );
+}
+
+#[trace(items)]
+fn squares(n: u32) -> impl Iterator<Item = u32> {
+    (1..=n).map(|i| i * i)
+}
+
+struct Numbers {
+    step: u32,
+}
+
+#[trace(items = 3, prefix = "Numbers::")]
+impl Numbers {
+    fn take_up_to(&self, max: u32) -> Box<dyn Iterator<Item = u32>> {
+        Box::new((0..max).step_by(self.step as usize))
+    }
+}
+
+#[trace(items, item = "{}")]
+fn split(text: &str) -> impl Iterator<Item = &str> {
+    text.split(' ')
+}
+
+#[trace(items)]
+fn doubles(
+    n: u8,
+) -> impl DoubleEndedIterator<Item = u8> + ExactSizeIterator + FusedIterator + Clone {
+    (0..n).map(|i| i * 2)
+}
@@ -0,0 +1,21 @@
+// Out-of-line modules as an input of an attribute macro are unstable,
+// the example is built on nightly with `RUSTFLAGS='--cfg nightly'`.
+#![cfg_attr(nightly, feature(proc_macro_hygiene))]
+
+#[cfg(nightly)]
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    #[cfg(nightly)]
+    {
+        let pool = db::Pool::new(2);
+        db::query::run(&pool, "SELECT 1");
+    }
+}
+
+// Reads `example_mod_file/db.rs`, and `example_mod_file/db/query.rs` declared within it.
+#[cfg(nightly)]
+#[trace]
+mod db;
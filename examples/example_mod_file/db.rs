@@ -0,0 +1,12 @@
+pub mod query;
+
+#[derive(Debug)]
+pub struct Pool {
+    size: usize,
+}
+
+impl Pool {
+    pub fn new(size: usize) -> Self {
+        Pool { size }
+    }
+}
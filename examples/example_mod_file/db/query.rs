@@ -0,0 +1,5 @@
+use super::Pool;
+
+pub fn run(pool: &Pool, sql: &str) -> usize {
+    pool.size + sql.len()
+}
@@ -0,0 +1,48 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let mut a = 10;
+    let mut b = vec![1, 2];
+    update(&mut a, &mut b);
+
+    let mut account = Account {
+        owner: "Alice".to_string(),
+        balance: 100,
+        history: vec![],
+    };
+    account.deposit(50);
+    account.rename("Bob");
+    account.balance();
+}
+
+#[trace(mut_after)]
+fn update(a: &mut u32, b: &mut Vec<u32>) {
+    *a += 20;
+    b.push(3);
+}
+
+#[derive(Debug)]
+struct Account {
+    owner: String,
+    balance: u64,
+    history: Vec<u64>,
+}
+
+#[trace(mut_after(self, diff), prefix = "Account::")]
+impl Account {
+    fn deposit(&mut self, amount: u64) {
+        self.balance += amount;
+        self.history.push(amount);
+    }
+
+    fn rename(&mut self, owner: &str) {
+        println!("renaming {} to {}", self.owner, owner);
+        self.owner = owner.to_string();
+    }
+
+    fn balance(&self) -> u64 {
+        self.balance
+    }
+}
@@ -13,7 +13,7 @@ struct Tracker {
     inner: HashMap<Vec<u8>, Instant>,
 }
 
-#[trace(disable(new), prefix = "Tracker::")]
+#[trace(disable(new), prefix = "Tracker::", sensitive())]
 impl Tracker {
     fn new() -> Self {
         Tracker {
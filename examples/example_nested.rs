@@ -0,0 +1,25 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    total(&[1, 2, 3]);
+}
+
+#[trace(nested, closures)]
+fn total(items: &[u32]) -> u32 {
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    let offset = 1;
+    let shift = |x: u32| x + offset;
+
+    // Options of the nested items are given as usual.
+    #[trace(disable(res))]
+    let describe = |sum| format!("sum {}", sum);
+
+    let sum = items.iter().map(|&x| shift(double(x))).sum();
+    describe(sum);
+    sum
+}
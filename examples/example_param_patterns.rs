@@ -0,0 +1,53 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let point = Point { x: 1, y: 2 };
+
+    norm(point);
+    swap(&(3, 4));
+    first(&[5, 6, 7]);
+    ignore(8, Wrapper(9));
+    origin(point);
+    whole((10, 11));
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug)]
+struct Wrapper(u8);
+
+#[trace]
+fn norm(Point { x, y }: Point) -> i32 {
+    x.abs() + y.abs()
+}
+
+#[trace]
+fn swap(&(a, b): &(u8, u8)) -> (u8, u8) {
+    (b, a)
+}
+
+#[trace]
+fn first(&[head, _, last]: &[u8; 3]) -> u8 {
+    head + last
+}
+
+#[trace(disable("_"))]
+fn ignore(_: u8, Wrapper(inner): Wrapper) -> u8 {
+    inner
+}
+
+#[trace]
+fn origin(Point { .. }: Point) -> Point {
+    Point { x: 0, y: 0 }
+}
+
+#[trace]
+fn whole(pair @ (a, _): (u8, u8)) -> u8 {
+    a + pair.1
+}
@@ -0,0 +1,52 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    outer::get_value(1);
+    let _ = outer::try_parse("2");
+    outer::ignored();
+    outer::inner::nested(3);
+
+    let f = outer::Foo;
+    f.bar(4);
+    f.baz(5);
+
+    disabled_args(6, 7, 8);
+}
+
+#[trace(enable("get_*", "/^try_/", "inner::*", "Foo::bar"))]
+mod outer {
+    pub(super) fn get_value(a: u32) -> u32 {
+        a
+    }
+
+    pub(super) fn try_parse(s: &str) -> Result<u32, std::num::ParseIntError> {
+        s.parse()
+    }
+
+    pub(super) fn ignored() {}
+
+    pub(super) mod inner {
+        pub(in super::super) fn nested(a: u32) -> u32 {
+            a
+        }
+    }
+
+    pub(super) struct Foo;
+
+    impl Foo {
+        pub(super) fn bar(&self, a: u32) -> u32 {
+            a
+        }
+
+        pub(super) fn baz(&self, a: u32) -> u32 {
+            a
+        }
+    }
+}
+
+#[trace(disable("secret_*"))]
+fn disabled_args(a: u32, secret_b: u32, secret_c: u32) -> u32 {
+    a + secret_b + secret_c
+}
@@ -0,0 +1,58 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let mut counter = Counter { id: 7, count: 0 };
+    counter.increment();
+    counter.increment();
+    println!("count: {}", counter.get());
+
+    let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square(2.0)), Box::new(Circle(1.0))];
+    for shape in &shapes {
+        shape.describe();
+    }
+}
+
+#[derive(Debug)]
+struct Counter {
+    id: u32,
+    count: u32,
+}
+
+#[trace(receiver("self.id", addr), prefix = "Counter::")]
+impl Counter {
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+
+    #[trace(receiver)]
+    fn get(&self) -> u32 {
+        self.count
+    }
+}
+
+#[trace(receiver(type))]
+trait Shape {
+    fn area(&self) -> f64;
+
+    fn describe(&self) -> String {
+        format!("area {}", self.area())
+    }
+}
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+struct Circle(f64);
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.0 * self.0
+    }
+}
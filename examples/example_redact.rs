@@ -0,0 +1,56 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let key = auth::login("alice", "hunter2");
+    auth::authorize(&key, "s3cr3t-t0k3n");
+    auth::authorize(&key, "s3cr3t-t0k3n");
+    auth::authorize(&key, "another-t0k3n");
+    let key = auth::rotate(key);
+    auth::store(key);
+    auth::unlock(&(Pin(1234), 3));
+    auth::lookup(&["alice", "bob"], "bob");
+}
+
+#[derive(Debug)]
+pub struct ApiKey(String);
+
+#[derive(Clone, Copy, Debug)]
+pub struct Pin(u16);
+
+// Values of type `ApiKey` are never logged; a parameter named like a secret, e.g., `password`,
+// which would be logged in full here, is reported by a warning at compile time.
+#[trace(fmt(ApiKey = redact, Pin = redact))]
+mod auth {
+    use super::{ApiKey, Pin};
+
+    #[trace(redact(password))]
+    pub fn login(user: &str, password: &str) -> ApiKey {
+        ApiKey(format!("{}:{}", user, password.len()))
+    }
+
+    // The calls with the same token are told apart from the others by its salted hash.
+    #[trace(token = redact(hash))]
+    pub fn authorize(key: &ApiKey, token: &str) -> bool {
+        !key.0.is_empty() && !token.is_empty()
+    }
+
+    pub fn rotate(key: ApiKey) -> ApiKey {
+        ApiKey(key.0.chars().rev().collect())
+    }
+
+    // The rules apply to parameters without a name and to destructured ones as well.
+    pub fn store(_: ApiKey) {}
+
+    pub fn unlock(&(pin, attempts): &(Pin, u8)) -> bool {
+        pin.0 % 10 == u16::from(attempts)
+    }
+
+    // A bare `key` is reported as well, unless `sensitive(...)` lists the name segments of secrets
+    // in place of the defaults.
+    #[trace(sensitive(password, secret, token))]
+    pub fn lookup(users: &[&str], key: &str) -> Option<usize> {
+        users.iter().position(|user| *user == key)
+    }
+}
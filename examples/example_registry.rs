@@ -0,0 +1,36 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    for function in trace::functions() {
+        println!(
+            "{} at {}:{}",
+            function.name(),
+            function.file(),
+            function.line()
+        );
+    }
+
+    foo(1);
+    trace::set_enabled("foo", false);
+    foo(2);
+
+    Foo::bar(3);
+    trace::set_enabled("Foo::bar", false);
+    Foo::bar(4);
+}
+
+#[trace]
+fn foo(a: i32) -> i32 {
+    a
+}
+
+struct Foo;
+
+#[trace]
+impl Foo {
+    fn bar(b: i32) -> i32 {
+        b
+    }
+}
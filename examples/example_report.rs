@@ -0,0 +1,35 @@
+// Run with
+// cargo run --features report --example example_report
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+    let _report = trace::report_at_exit();
+
+    for i in 0..3 {
+        Foo::bar(i);
+    }
+    foo(1);
+}
+
+#[trace]
+fn foo(a: i32) -> i32 {
+    if a > 10 {
+        Foo::baz(a)
+    } else {
+        a
+    }
+}
+
+struct Foo;
+
+#[trace]
+impl Foo {
+    fn bar(b: i32) -> i32 {
+        b
+    }
+
+    fn baz(b: i32) -> i32 {
+        b
+    }
+}
@@ -0,0 +1,39 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let greet = make_greeter("Hello");
+    greet("Alice");
+    greet("Bob");
+
+    let mut count = make_counter(10);
+    count(1);
+    count(2);
+
+    let handler = Handlers.make_handler(3);
+    println!("handled: {}", handler(4, 5));
+}
+
+#[trace(returned_closure)]
+fn make_greeter(greeting: &'static str) -> impl Fn(&str) -> String {
+    move |name: &str| format!("{}, {}!", greeting, name)
+}
+
+#[trace(returned_closure, _0 = "+{}")]
+fn make_counter(start: u32) -> Box<dyn FnMut(u32) -> u32> {
+    let mut total = start;
+    Box::new(move |step| {
+        total += step;
+        total
+    })
+}
+
+struct Handlers;
+
+#[trace(returned_closure, prefix = "Handlers::")]
+impl Handlers {
+    fn make_handler(&self, factor: u8) -> impl Fn(u8, u8) -> u8 {
+        move |a, b| factor * (a + b)
+    }
+}
@@ -0,0 +1,39 @@
+// Run with
+// RUST_LOG=trace TRACE_FILTER="example_runtime_filter::db=on,example_runtime_filter::db::Pool::get=off,*=off" \
+// cargo run --example example_runtime_filter
+//
+// Functions can also be excluded at compile time, e.g., with
+// TRACE_COMPILE_FILTER="*=off,example_runtime_filter::db" cargo run --example example_runtime_filter
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let pool = db::Pool::new();
+    pool.get(1);
+    db::connect();
+    foo(2);
+}
+
+#[trace]
+fn foo(a: u32) -> u32 {
+    a
+}
+
+#[trace]
+mod db {
+    #[derive(Debug)]
+    pub(super) struct Pool;
+
+    impl Pool {
+        pub(super) fn new() -> Self {
+            Self
+        }
+
+        pub(super) fn get(&self, id: u32) -> u32 {
+            id
+        }
+    }
+
+    pub(super) fn connect() {}
+}
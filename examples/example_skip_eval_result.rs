@@ -9,7 +9,7 @@ fn main() {
 
 struct A;
 
-#[trace(prefix = "A::", disable(new))]
+#[trace(prefix = "A::")]
 impl A {
     #[trace(disable(res))]
     fn returns_convoluted_result(a: u8) -> (Duration, u8, B) {
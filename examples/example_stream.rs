@@ -0,0 +1,26 @@
+use std::pin::Pin;
+use tokio::stream::{self, Stream, StreamExt};
+use trace::trace;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let mut numbers = numbers(3);
+    while let Some(number) = numbers.next().await {
+        println!("number: {}", number);
+    }
+
+    let letters = letters().take(2).collect::<Vec<_>>().await;
+    println!("letters: {:?}", letters);
+}
+
+#[trace(items)]
+fn numbers(n: u8) -> impl Stream<Item = u8> {
+    stream::iter(0..n)
+}
+
+#[trace(items)]
+fn letters() -> Pin<Box<dyn Stream<Item = char> + Send>> {
+    Box::pin(stream::iter(vec!['a', 'b', 'c']))
+}
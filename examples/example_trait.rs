@@ -0,0 +1,44 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let square = Square::with_side_traced(2.0);
+
+    square.describe();
+    square.area_traced();
+    square.scale_traced(3.0, true);
+}
+
+#[trace]
+trait Shape {
+    // A required method, callable traced as `area_traced`.
+    fn area(&self) -> f64;
+
+    // Parameters without a name are traced as `_`, as in functions with a body.
+    fn scale(&self, factor: f64, _: bool) -> f64;
+
+    // A wrapper returning `Self` is bounded by `Self: Sized`.
+    fn with_side(side: f64) -> Self;
+
+    #[trace(prefix = "[default] ")]
+    fn describe(&self) -> String {
+        format!("a shape of area {}", self.area())
+    }
+}
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+
+    fn scale(&self, factor: f64, _: bool) -> f64 {
+        self.area() * factor * factor
+    }
+
+    fn with_side(side: f64) -> Self {
+        Square(side)
+    }
+}
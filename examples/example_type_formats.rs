@@ -0,0 +1,53 @@
+use std::fmt;
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let id = store::insert(b"payload".to_vec());
+    store::get(&id);
+    store::tag(&(id, 7));
+    store::touch(id);
+    store::Cache::default().put(id, vec![0xff; 3]);
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Uuid(u128);
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:08x}-...", self.0 >> 96)
+    }
+}
+
+// The rules apply to every argument and result of these types, whatever their names.
+#[trace(fmt(Vec<u8> = hex, Uuid = "{}"))]
+mod store {
+    use super::Uuid;
+
+    pub fn insert(data: Vec<u8>) -> Uuid {
+        Uuid(data.len() as u128 * 0x1234_5678_9abc_def0_1234_5678_9abc_def0)
+    }
+
+    pub fn get(key: &Uuid) -> Option<Vec<u8>> {
+        Some(key.0.to_le_bytes()[..4].to_vec())
+    }
+
+    // The types of destructured parameters are followed through tuples and references.
+    pub fn tag(&(id, tag): &(Uuid, u8)) -> Vec<u8> {
+        vec![id.0 as u8, tag]
+    }
+
+    pub fn touch(_: Uuid) {}
+
+    #[derive(Default)]
+    pub struct Cache(Vec<(Uuid, Vec<u8>)>);
+
+    // The rules of the module are inherited, a formatting by name still wins.
+    #[trace(value = len)]
+    impl Cache {
+        pub fn put(&mut self, id: Uuid, value: Vec<u8>) {
+            self.0.push((id, value));
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let _: Result<u64, _> = parse("42");
+    let _: Result<f32, _> = parse(String::from("1.5"));
+    largest(&[3, 7, 5]);
+    largest(&["b", "a"]);
+}
+
+#[trace(types)]
+fn parse<T: FromStr + Debug, S: AsRef<str> + Debug>(input: S) -> Result<T, T::Err>
+where
+    T::Err: Debug,
+{
+    input.as_ref().parse()
+}
+
+#[trace(types)]
+fn largest<T: PartialOrd + Copy + Debug>(items: &[T]) -> T {
+    let mut largest = items[0];
+    for &item in items {
+        if item > largest {
+            largest = item;
+        }
+    }
+    largest
+}
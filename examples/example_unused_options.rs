@@ -0,0 +1,28 @@
+use trace::trace;
+
+fn main() {
+    env_logger::init();
+
+    let counter = Counter::new(1);
+    counter.add(2);
+}
+
+struct Counter {
+    start: u8,
+}
+
+// `disable(step)` and `items` have no effect, as no method is named `step` or returns an iterator;
+// without the `allow`, building this example reports:
+// warning: use of deprecated constant `_::__trace_unused_argument__`: `step` in `disable` matches no function
+// warning: use of deprecated constant `_::__trace_unused_argument__`: `items` applies to no function returning an iterator or a stream
+#[trace(prefix = "Counter::", disable(step), items)]
+#[allow(deprecated)]
+impl Counter {
+    fn new(start: u8) -> Self {
+        Self { start }
+    }
+
+    fn add(&self, n: u8) -> u8 {
+        self.start + n
+    }
+}
@@ -0,0 +1,126 @@
+//! Runtime filter parsed from the `TRACE_FILTER` environment variable.
+//!
+//! The variable holds a comma separated list of directives `<pattern>[=on|off]`,
+//! e.g., `TRACE_FILTER="my_crate::db=on,my_crate::db::Pool::get=off,*=off"`.
+//! A pattern is matched against the qualified name of an instrumented function,
+//! `<module path>::[<type>::]<function>`, and selects the function itself as well as
+//! everything nested below it. `*` matches any sequence of characters, `?` any single character.
+//! If several directives match a name, the longest pattern wins.
+//! Without `TRACE_FILTER` all instrumented functions are enabled.
+
+use std::env;
+use std::sync::OnceLock;
+
+pub(crate) const ENV_VAR: &str = "TRACE_FILTER";
+
+pub(crate) struct Filter {
+    directives: Vec<Directive>,
+}
+
+struct Directive {
+    pattern: String,
+    enabled: bool,
+}
+
+impl Filter {
+    /// Filter configured by the environment, parsed on the first use.
+    pub(crate) fn global() -> &'static Filter {
+        static FILTER: OnceLock<Filter> = OnceLock::new();
+
+        FILTER.get_or_init(|| match env::var(ENV_VAR) {
+            Ok(spec) => Filter::parse(&spec),
+            Err(_) => Filter {
+                directives: Vec::new(),
+            },
+        })
+    }
+
+    pub(crate) fn parse(spec: &str) -> Self {
+        let mut directives = Vec::new();
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let (pattern, enabled) = match directive.rfind('=') {
+                None => (directive, Some(true)),
+                Some(pos) => (
+                    directive[..pos].trim(),
+                    match directive[pos + 1..].trim() {
+                        "on" => Some(true),
+                        "off" => Some(false),
+                        _ => None,
+                    },
+                ),
+            };
+
+            match enabled {
+                Some(enabled) if !pattern.is_empty() => directives.push(Directive {
+                    pattern: pattern.to_string(),
+                    enabled,
+                }),
+                _ => log::warn!(
+                    "invalid directive `{}` in {}, expected `<pattern>[=on|off]`",
+                    directive,
+                    ENV_VAR
+                ),
+            }
+        }
+
+        Filter { directives }
+    }
+
+    /// Decides whether a function with the qualified `name` must be traced.
+    pub(crate) fn enabled(&self, name: &str) -> bool {
+        let mut best: Option<&Directive> = None;
+
+        for directive in self.directives.iter().filter(|d| d.matches(name)) {
+            match best {
+                Some(b) if b.pattern.len() > directive.pattern.len() => (),
+                _ => best = Some(directive),
+            }
+        }
+
+        best.is_none_or(|directive| directive.enabled)
+    }
+}
+
+impl Directive {
+    /// The pattern must match either the whole name or one of its `::`-delimited prefixes.
+    fn matches(&self, name: &str) -> bool {
+        let pattern = self.pattern.as_bytes();
+
+        glob(pattern, name.as_bytes())
+            || name
+                .match_indices("::")
+                .any(|(pos, _)| glob(pattern, &name.as_bytes()[..pos]))
+    }
+}
+
+/// Matches `text` against `pattern` where `*` stands for any sequence of characters
+/// and `?` for any single character.
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and of the text it was matched against.
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
@@ -0,0 +1,34 @@
+//! Registry of all functions instrumented with `#[trace]` in the program.
+//!
+//! Functions are collected at link time, hence they are listed even if they have never been called.
+
+use crate::runtime::{Callsite, Registration};
+
+/// Lists all instrumented functions.
+pub fn functions() -> impl Iterator<Item = &'static Callsite> {
+    inventory::iter::<Registration>
+        .into_iter()
+        .map(|registration| registration.0)
+}
+
+/// Enables or disables tracing of the functions named `name`, overriding the runtime filter.
+///
+/// `name` is either a fully qualified name, e.g., `my_crate::db::Pool::get`, or its suffix
+/// starting at a path segment, e.g., `Pool::get`.
+/// Returns the number of affected functions.
+pub fn set_enabled(name: &str, enabled: bool) -> usize {
+    let mut count = 0;
+
+    for callsite in functions().filter(|callsite| matches_name(callsite.name(), name)) {
+        callsite.set_enabled(enabled);
+        count += 1;
+    }
+
+    count
+}
+
+fn matches_name(qualified_name: &str, name: &str) -> bool {
+    qualified_name == name
+        || (qualified_name.ends_with(name)
+            && qualified_name[..qualified_name.len() - name.len()].ends_with("::"))
+}
@@ -0,0 +1,103 @@
+//! Coverage-style report of instrumented functions, available with the `report` feature.
+//!
+//! The report is taken on demand with [`report`](fn.report.html), or printed when `main` returns
+//! by keeping the guard returned by [`report_at_exit`](fn.report_at_exit.html) alive.
+
+use crate::registry;
+use crate::runtime::Callsite;
+use std::fmt;
+
+/// Snapshot of the number of calls of every instrumented function.
+pub struct Report {
+    entries: Vec<Entry>,
+}
+
+pub struct Entry {
+    pub function: &'static Callsite,
+    pub calls: usize,
+}
+
+/// Takes a snapshot of the number of calls of every instrumented function.
+pub fn report() -> Report {
+    let mut entries = registry::functions()
+        .map(|function| Entry {
+            function,
+            calls: function.calls(),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| {
+        b.calls
+            .cmp(&a.calls)
+            .then_with(|| a.function.name().cmp(b.function.name()))
+    });
+
+    Report { entries }
+}
+
+/// Returns a guard printing the report to stderr when dropped.
+#[must_use = "the report is printed when the guard is dropped"]
+pub fn report_at_exit() -> ReportGuard {
+    ReportGuard(())
+}
+
+pub struct ReportGuard(());
+
+impl Drop for ReportGuard {
+    fn drop(&mut self) {
+        eprint!("{}", report());
+    }
+}
+
+impl Report {
+    /// Functions that have been called at least once, the most called first.
+    pub fn called(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(|entry| entry.calls > 0)
+    }
+
+    /// Functions that have never been called.
+    pub fn never_called(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(|entry| entry.calls == 0)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let called = self.called().count();
+
+        writeln!(
+            f,
+            "{} instrumented functions, {} called, {} never called",
+            self.entries.len(),
+            called,
+            self.entries.len() - called
+        )?;
+
+        if called > 0 {
+            writeln!(f, "called:")?;
+            for entry in self.called() {
+                writeln!(f, "\t{:>8}  {}", entry.calls, entry)?;
+            }
+        }
+
+        if called < self.entries.len() {
+            writeln!(f, "never called:")?;
+            for entry in self.never_called() {
+                writeln!(f, "\t{}", entry)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}:{})",
+            self.function.name(),
+            self.function.file(),
+            self.function.line()
+        )
+    }
+}
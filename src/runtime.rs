@@ -0,0 +1,793 @@
+//! Items referenced by the code generated by `#[trace]`.
+//!
+//! Generated code refers to this module through the path of the `trace` crate,
+//! so users do not have to depend on `log` themselves.
+
+use crate::filter::Filter;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::iter::FusedIterator;
+use std::pin::Pin;
+#[cfg(feature = "report")]
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use std::task::{Context, Poll};
+
+#[doc(hidden)]
+pub use inventory;
+#[doc(hidden)]
+pub use log;
+
+const INTEREST_UNKNOWN: u8 = 0;
+const INTEREST_NEVER: u8 = 1;
+const INTEREST_ALWAYS: u8 = 2;
+
+/// An instrumented function, declared as a `static` in its body.
+///
+/// Whether the function is traced is decided by the runtime filter on the first call
+/// and cached, so that afterwards a check costs a single atomic load.
+pub struct Callsite {
+    name: &'static str,
+    file: &'static str,
+    line: u32,
+    interest: AtomicU8,
+    #[cfg(feature = "report")]
+    calls: AtomicUsize,
+}
+
+impl Callsite {
+    pub const fn new(name: &'static str, file: &'static str, line: u32) -> Self {
+        Self {
+            name,
+            file,
+            line,
+            interest: AtomicU8::new(INTEREST_UNKNOWN),
+            #[cfg(feature = "report")]
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Qualified name of the function, `<module path>::[<type>::]<function>`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Source file the function is defined in.
+    pub fn file(&self) -> &'static str {
+        self.file
+    }
+
+    /// Line the function is defined at.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Number of times the function has been called.
+    #[cfg(feature = "report")]
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// Records a call of the function and tells whether it must be traced.
+    #[doc(hidden)]
+    #[inline]
+    pub fn enter(&self) -> bool {
+        #[cfg(feature = "report")]
+        self.calls.fetch_add(1, Ordering::Relaxed);
+
+        self.is_enabled()
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        match self.interest.load(Ordering::Relaxed) {
+            INTEREST_NEVER => false,
+            INTEREST_ALWAYS => true,
+            _ => self.register(),
+        }
+    }
+
+    /// Overrides the decision of the runtime filter.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.interest.store(interest(enabled), Ordering::Relaxed);
+    }
+
+    #[cold]
+    fn register(&self) -> bool {
+        let enabled = Filter::global().enabled(self.name);
+        self.interest.store(interest(enabled), Ordering::Relaxed);
+
+        enabled
+    }
+}
+
+fn interest(enabled: bool) -> u8 {
+    if enabled {
+        INTEREST_ALWAYS
+    } else {
+        INTEREST_NEVER
+    }
+}
+
+/// Entry of the registry of instrumented functions, submitted next to each `Callsite`.
+#[doc(hidden)]
+pub struct Registration(pub &'static Callsite);
+
+inventory::collect!(Registration);
+
+/// A future returned by an instrumented function, which is not an `async fn`,
+/// e.g., `fn get(&self) -> impl Future<Output = u8>` or a method expanded by `async-trait`.
+///
+/// Calls `on_exit` with the output once the future completes, so that the exit of the function
+/// is reported on completion rather than when the future is created.
+#[doc(hidden)]
+pub struct TracedFuture<F: Future, C: FnOnce(&F::Output)> {
+    future: F,
+    on_exit: Option<C>,
+}
+
+impl<F: Future, C: FnOnce(&F::Output)> TracedFuture<F, C> {
+    pub fn new(future: F, on_exit: C) -> Self {
+        Self {
+            future,
+            on_exit: Some(on_exit),
+        }
+    }
+}
+
+impl<F: Future, C: FnOnce(&F::Output)> Future for TracedFuture<F, C> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is pinned along with `self` and never moved out of it,
+        // `on_exit` is not pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        match future.poll(cx) {
+            Poll::Ready(output) => {
+                if let Some(on_exit) = this.on_exit.take() {
+                    on_exit(&output);
+                }
+                Poll::Ready(output)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Tracing state of the items of an iterator or a stream returned by an instrumented function
+/// with `items`.
+///
+/// Calls `on_item` with the index and the value of each of the first `limit` items,
+/// and `on_end` with the number of items and whether the sequence has been exhausted
+/// once it ends or is dropped.
+struct Items<T, E: FnOnce(usize, bool)> {
+    limit: usize,
+    count: usize,
+    on_item: T,
+    on_end: Option<E>,
+}
+
+impl<T, E: FnOnce(usize, bool)> Items<T, E> {
+    fn new(limit: usize, on_item: T, on_end: E) -> Self {
+        Self {
+            limit,
+            count: 0,
+            on_item,
+            on_end: Some(on_end),
+        }
+    }
+
+    fn next<V>(&mut self, item: Option<&V>)
+    where
+        T: Fn(usize, &V),
+    {
+        match item {
+            Some(item) => {
+                if self.count < self.limit {
+                    (self.on_item)(self.count, item);
+                }
+                self.count += 1;
+            }
+            None => self.end(true),
+        }
+    }
+
+    fn end(&mut self, exhausted: bool) {
+        if let Some(on_end) = self.on_end.take() {
+            on_end(self.count, exhausted);
+        }
+    }
+}
+
+// A clone goes on from the items seen so far, and reports its own end.
+impl<T: Clone, E: FnOnce(usize, bool) + Clone> Clone for Items<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            limit: self.limit,
+            count: self.count,
+            on_item: self.on_item.clone(),
+            on_end: self.on_end.clone(),
+        }
+    }
+}
+
+impl<T, E: FnOnce(usize, bool)> Drop for Items<T, E> {
+    fn drop(&mut self) {
+        self.end(false);
+    }
+}
+
+/// An iterator returned by an instrumented function with `items`.
+///
+/// Implements `Clone`, `DoubleEndedIterator`, `ExactSizeIterator` and `FusedIterator`
+/// if the inner iterator does, so that they can be bounds of the returned `impl Iterator`.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct TracedIter<I, T, E: FnOnce(usize, bool)> {
+    inner: I,
+    items: Items<T, E>,
+}
+
+impl<I: Iterator, T: Fn(usize, &I::Item), E: FnOnce(usize, bool)> TracedIter<I, T, E> {
+    pub fn new(inner: I, limit: usize, on_item: T, on_end: E) -> Self {
+        Self {
+            inner,
+            items: Items::new(limit, on_item, on_end),
+        }
+    }
+}
+
+impl<I: Iterator, T: Fn(usize, &I::Item), E: FnOnce(usize, bool)> Iterator for TracedIter<I, T, E> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next();
+        self.items.next(item.as_ref());
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+// Items taken from the back are logged in the order they are yielded.
+impl<I: DoubleEndedIterator, T: Fn(usize, &I::Item), E: FnOnce(usize, bool)> DoubleEndedIterator
+    for TracedIter<I, T, E>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next_back();
+        self.items.next(item.as_ref());
+        item
+    }
+}
+
+impl<I: ExactSizeIterator, T: Fn(usize, &I::Item), E: FnOnce(usize, bool)> ExactSizeIterator
+    for TracedIter<I, T, E>
+{
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<I: FusedIterator, T: Fn(usize, &I::Item), E: FnOnce(usize, bool)> FusedIterator
+    for TracedIter<I, T, E>
+{
+}
+
+/// A stream returned by an instrumented function with `items`.
+///
+/// Implements `Clone` and `FusedStream` if the inner stream does.
+#[cfg(feature = "stream")]
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct TracedStream<S, T, E: FnOnce(usize, bool)> {
+    inner: S,
+    items: Items<T, E>,
+}
+
+#[cfg(feature = "stream")]
+impl<S: futures_core::Stream, T: Fn(usize, &S::Item), E: FnOnce(usize, bool)>
+    TracedStream<S, T, E>
+{
+    pub fn new(inner: S, limit: usize, on_item: T, on_end: E) -> Self {
+        Self {
+            inner,
+            items: Items::new(limit, on_item, on_end),
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<S: futures_core::Stream, T: Fn(usize, &S::Item), E: FnOnce(usize, bool)> futures_core::Stream
+    for TracedStream<S, T, E>
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // SAFETY: `inner` is pinned along with `self` and never moved out of it,
+        // `items` is not pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+
+        match inner.poll_next(cx) {
+            Poll::Ready(item) => {
+                this.items.next(item.as_ref());
+                Poll::Ready(item)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<S: futures_core::stream::FusedStream, T: Fn(usize, &S::Item), E: FnOnce(usize, bool)>
+    futures_core::stream::FusedStream for TracedStream<S, T, E>
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+/// Lists the changes between two `{:#?}` representations of a value named `name`,
+/// one per line, e.g., `self.count: 1 -> 2`, as logged by `mut_after(diff)`.
+#[doc(hidden)]
+pub fn debug_diff(name: &str, before: &str, after: &str) -> String {
+    let before = flatten_debug(name, before);
+    let after = flatten_debug(name, after);
+
+    let value_of = |entries: &[(String, String)], path: &str| {
+        entries
+            .iter()
+            .find(|(other, _)| other == path)
+            .map(|(_, value)| value.clone())
+    };
+
+    let mut changes = Vec::new();
+    for (path, value) in &after {
+        match value_of(&before, path) {
+            Some(ref old) if old == value => (),
+            Some(old) => changes.push(format!("{}: {} -> {}", path, old, value)),
+            None => changes.push(format!("{}: (none) -> {}", path, value)),
+        }
+    }
+    for (path, value) in &before {
+        if value_of(&after, path).is_none() {
+            changes.push(format!("{}: {} -> (none)", path, value));
+        }
+    }
+
+    if changes.is_empty() {
+        format!("{}: unchanged", name)
+    } else {
+        changes.join("\n\t")
+    }
+}
+
+/// Splits a `{:#?}` representation into the values of its fields and elements, by their paths,
+/// e.g., `self.items[0]`; a nested value is listed by its type, e.g., `Some` or `Point`.
+fn flatten_debug(name: &str, repr: &str) -> Vec<(String, String)> {
+    /// A struct, a map or a sequence being read, with the number of its unnamed elements so far.
+    struct Scope {
+        path: String,
+        named: bool,
+        list: bool,
+        index: usize,
+    }
+
+    let mut entries = Vec::new();
+    let mut scopes: Vec<Scope> = Vec::new();
+
+    for line in repr.lines() {
+        let line = line.trim();
+        let line = line.strip_suffix(',').unwrap_or(line);
+
+        if line.starts_with(['}', ']', ')']) {
+            scopes.pop();
+            continue;
+        }
+
+        let (path, value) = match scopes.last_mut() {
+            None => (name.to_string(), line),
+            Some(scope) if scope.named => match line.split_once(": ") {
+                Some((key, value)) => (format!("{}.{}", scope.path, key), value),
+                None => (format!("{}.{}", scope.path, line), line),
+            },
+            Some(scope) => {
+                let path = if scope.list {
+                    format!("{}[{}]", scope.path, scope.index)
+                } else {
+                    format!("{}.{}", scope.path, scope.index)
+                };
+                scope.index += 1;
+                (path, line)
+            }
+        };
+
+        match value.chars().last() {
+            Some(open @ '{') | Some(open @ '[') | Some(open @ '(') => {
+                let type_name = value[..value.len() - 1].trim();
+                if !type_name.is_empty() {
+                    entries.push((path.clone(), type_name.to_string()));
+                }
+                scopes.push(Scope {
+                    path,
+                    named: open == '{',
+                    list: open == '[',
+                    index: 0,
+                });
+            }
+            // Empty sequences and maps are told by the absence of elements.
+            _ if value == "[]" || value == "{}" => (),
+            _ => entries.push((path, value.to_string())),
+        }
+    }
+
+    entries
+}
+
+/// A value logged with `{:?}` by the code generated by `#[trace]`,
+/// whose type need not implement `Debug`.
+///
+/// `(&Value(&x)).__trace_logged__()` resolves to `LogDebug` if the type of `x` implements `Debug`,
+/// as its method takes `Value` by reference, and to `LogFallback` otherwise,
+/// as its method takes `&Value` by reference, i.e., requires one more auto-reference.
+#[doc(hidden)]
+pub struct Value<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait LogDebug<'a, T: ?Sized> {
+    fn __trace_logged__(&self) -> &'a T;
+}
+
+impl<'a, T: Debug + ?Sized> LogDebug<'a, T> for Value<'a, T> {
+    fn __trace_logged__(&self) -> &'a T {
+        self.0
+    }
+}
+
+#[doc(hidden)]
+pub trait LogFallback {
+    fn __trace_logged__(&self) -> Opaque;
+}
+
+impl<'a, T: ?Sized> LogFallback for &Value<'a, T> {
+    fn __trace_logged__(&self) -> Opaque {
+        Opaque {
+            type_name: std::any::type_name::<T>(),
+            size: std::mem::size_of_val(self.0),
+        }
+    }
+}
+
+/// A value whose type does not implement `Debug`, logged as `<Connection: 48 bytes>`.
+#[doc(hidden)]
+pub struct Opaque {
+    type_name: &'static str,
+    size: usize,
+}
+
+impl Debug for Opaque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<{}: {} bytes>",
+            short_type_name(self.type_name),
+            self.size
+        )
+    }
+}
+
+/// A value logged by a function given with `with(...)`, e.g., `fn hex(x: &u32, f: &mut Formatter)`.
+#[doc(hidden)]
+pub struct With<'a, T: ?Sized, F>(pub &'a T, pub F);
+
+impl<T: ?Sized, F> Debug for With<'_, T, F>
+where
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+/// A value logged by a built-in format, e.g., `data = hex`.
+#[doc(hidden)]
+pub type Formatted<'a, T> = With<'a, T, fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result>;
+
+/// `(&&&Value(&x)).__trace_hex__()` resolves, like `__trace_logged__`, to the first of
+/// `HexBytes`, `HexInt`, `HexDebug` and `HexFallback` that applies to the type of `x`,
+/// as each takes one less reference.
+#[doc(hidden)]
+pub trait HexBytes<'a, T: ?Sized> {
+    fn __trace_hex__(&self) -> Formatted<'a, T>;
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> HexBytes<'a, T> for &&Value<'a, T> {
+    fn __trace_hex__(&self) -> Formatted<'a, T> {
+        With(self.0, |value, f| {
+            value
+                .as_ref()
+                .iter()
+                .try_for_each(|byte| write!(f, "{:02x}", byte))
+        })
+    }
+}
+
+#[doc(hidden)]
+pub trait HexInt<'a, T: ?Sized> {
+    fn __trace_hex__(&self) -> Formatted<'a, T>;
+}
+
+impl<'a, T: fmt::LowerHex + ?Sized> HexInt<'a, T> for &&&Value<'a, T> {
+    fn __trace_hex__(&self) -> Formatted<'a, T> {
+        With(self.0, |value, f| write!(f, "{:#x}", value))
+    }
+}
+
+#[doc(hidden)]
+pub trait HexDebug<'a, T: ?Sized> {
+    fn __trace_hex__(&self) -> &'a T;
+}
+
+impl<'a, T: Debug + ?Sized> HexDebug<'a, T> for &Value<'a, T> {
+    fn __trace_hex__(&self) -> &'a T {
+        self.0
+    }
+}
+
+#[doc(hidden)]
+pub trait HexFallback {
+    fn __trace_hex__(&self) -> Opaque;
+}
+
+impl<T: ?Sized> HexFallback for Value<'_, T> {
+    fn __trace_hex__(&self) -> Opaque {
+        (&self).__trace_logged__()
+    }
+}
+
+/// A collection whose number of elements is logged by `len`.
+///
+/// Implement it to log the length of your own collections, e.g., `#[trace(queue = len)]`.
+#[allow(clippy::len_without_is_empty)]
+pub trait Len {
+    fn len(&self) -> usize;
+}
+
+macro_rules! impl_len {
+    ($($ty:ty $(, $param:ident)*;)*) => {
+        $(
+            impl<$($param),*> Len for $ty {
+                fn len(&self) -> usize {
+                    <$ty>::len(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_len! {
+    str;
+    String;
+    [T], T;
+    Vec<T>, T;
+    std::collections::VecDeque<T>, T;
+    std::collections::LinkedList<T>, T;
+    std::collections::BinaryHeap<T>, T;
+    std::collections::BTreeSet<T>, T;
+    std::collections::BTreeMap<K, V>, K, V;
+    std::collections::HashSet<T, S>, T, S;
+    std::collections::HashMap<K, V, S>, K, V, S;
+}
+
+impl<T, const N: usize> Len for [T; N] {
+    fn len(&self) -> usize {
+        N
+    }
+}
+
+impl<C: Len + ?Sized> Len for &C {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<C: Len + ?Sized> Len for &mut C {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<C: Len + ?Sized> Len for Box<C> {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<C: Len + ?Sized> Len for std::rc::Rc<C> {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<C: Len + ?Sized> Len for std::sync::Arc<C> {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+/// `(&&Value(&x)).__trace_len__()` resolves to the first of `LenCollection`, `LenDebug`
+/// and `LenFallback` that applies to the type of `x`.
+#[doc(hidden)]
+pub trait LenCollection<'a, T: ?Sized> {
+    fn __trace_len__(&self) -> Formatted<'a, T>;
+}
+
+impl<'a, T: Len + ?Sized> LenCollection<'a, T> for &Value<'a, T> {
+    fn __trace_len__(&self) -> Formatted<'a, T> {
+        With(self.0, |value, f| write!(f, "<len {}>", value.len()))
+    }
+}
+
+#[doc(hidden)]
+pub trait LenDebug<'a, T: ?Sized> {
+    fn __trace_len__(&self) -> &'a T;
+}
+
+impl<'a, T: Debug + ?Sized> LenDebug<'a, T> for &&Value<'a, T> {
+    fn __trace_len__(&self) -> &'a T {
+        self.0
+    }
+}
+
+#[doc(hidden)]
+pub trait LenFallback {
+    fn __trace_len__(&self) -> Opaque;
+}
+
+impl<T: ?Sized> LenFallback for Value<'_, T> {
+    fn __trace_len__(&self) -> Opaque {
+        (&self).__trace_logged__()
+    }
+}
+
+/// `(&Value(&x)).__trace_addr__()` logs the address `x` points to if it is a pointer,
+/// e.g., a reference or a `Box`, and the address of `x` otherwise.
+#[doc(hidden)]
+pub trait AddrPointer<'a, T: ?Sized> {
+    fn __trace_addr__(&self) -> Formatted<'a, T>;
+}
+
+impl<'a, T: fmt::Pointer + ?Sized> AddrPointer<'a, T> for Value<'a, T> {
+    fn __trace_addr__(&self) -> Formatted<'a, T> {
+        With(self.0, |value, f| fmt::Pointer::fmt(value, f))
+    }
+}
+
+#[doc(hidden)]
+pub trait AddrValue<'a, T: ?Sized> {
+    fn __trace_addr__(&self) -> Formatted<'a, T>;
+}
+
+impl<'a, T: ?Sized> AddrValue<'a, T> for &Value<'a, T> {
+    fn __trace_addr__(&self) -> Formatted<'a, T> {
+        With(self.0, |value, f| fmt::Pointer::fmt(&value, f))
+    }
+}
+
+/// A `Debug` output cut after a number of bytes, as logged by `truncate(N)`.
+#[doc(hidden)]
+pub struct Truncate<D>(pub D, pub usize);
+
+impl<D: Debug> Debug for Truncate<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let output = if f.alternate() {
+            format!("{:#?}", self.0)
+        } else {
+            format!("{:?}", self.0)
+        };
+        if output.len() <= self.1 {
+            return f.write_str(&output);
+        }
+        let mut end = self.1;
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        write!(
+            f,
+            "{}\u{2026}(+{} bytes)",
+            &output[..end],
+            output.len() - end
+        )
+    }
+}
+
+/// A short hash of a `Debug` output, as logged by `hash`, e.g., `#5f3a91c2`.
+#[doc(hidden)]
+pub struct DebugHash<D>(pub D);
+
+impl<D: Debug> Debug for DebugHash<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:08x}", short_hash(format!("{:?}", self.0).as_bytes()))
+    }
+}
+
+/// A value logged as `<redacted>` by `redact`.
+#[doc(hidden)]
+pub struct Redacted;
+
+impl Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// A value logged by `redact(hash)` as `<redacted #5f3a91c2>`, a hash of its `Debug` output.
+///
+/// The hash is keyed with a salt drawn once per process, so that equal values can be told apart
+/// within a run, while the values cannot be recovered by hashing candidates.
+#[doc(hidden)]
+pub struct RedactedHash<D>(pub D);
+
+impl<D: Debug> Debug for RedactedHash<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        static SALT: OnceLock<RandomState> = OnceLock::new();
+
+        let mut hasher = SALT.get_or_init(RandomState::new).build_hasher();
+        hasher.write(format!("{:?}", self.0).as_bytes());
+        let hash = hasher.finish();
+        write!(f, "<redacted #{:08x}>", (hash ^ (hash >> 32)) as u32)
+    }
+}
+
+/// FNV-1a folded to 32 bits, which unlike `DefaultHasher` is the same across runs and Rust versions.
+fn short_hash(bytes: &[u8]) -> u32 {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    (hash ^ (hash >> 32)) as u32
+}
+
+/// The name of type `T`, as logged by `types`.
+#[doc(hidden)]
+pub fn type_name<T: ?Sized>() -> String {
+    short_type_name(std::any::type_name::<T>())
+}
+
+/// The name of the type of a value, as logged by `types`.
+#[doc(hidden)]
+pub fn type_name_of_val<T: ?Sized>(_value: &T) -> String {
+    type_name::<T>()
+}
+
+/// Drops the paths from a type name, e.g., `Vec<Connection>` rather than `alloc::vec::Vec<app::db::Connection>`.
+fn short_type_name(type_name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    let mut chars = type_name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            segment.clear();
+        } else if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else {
+            short.push_str(&segment);
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(&segment);
+
+    short
+}
@@ -0,0 +1,1524 @@
+use crate::pattern::{Pattern, Segment};
+use quote::ToTokens;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap};
+use std::iter::FromIterator;
+use std::rc::Rc;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{self, spanned::Spanned};
+
+#[derive(Clone)]
+pub(crate) struct Args {
+    pub(crate) prefix_enter: Prefix,
+    pub(crate) prefix_exit: Prefix,
+    pub(crate) filter: Filter,
+    pub(crate) pause: bool,
+    pub(crate) pretty: bool,
+    pub(crate) only_pub: bool,
+    pub(crate) skip_trivial: bool,
+    pub(crate) skip_trait_impls: Vec<Pattern>,
+    pub(crate) off: bool,
+    pub(crate) inherit: bool,
+    pub(crate) nested: bool,
+    pub(crate) closures: bool,
+    pub(crate) items: Option<(proc_macro2::Span, usize)>,
+    pub(crate) returned_closure: Option<proc_macro2::Span>,
+    pub(crate) mut_after: Option<(proc_macro2::Span, MutAfter)>,
+    pub(crate) receiver: Option<(proc_macro2::Span, Receiver)>,
+    pub(crate) types: bool,
+    pub(crate) default_format: Option<Builtin>,
+    pub(crate) args_format: HashMap<proc_macro2::Ident, ArgFormat>,
+    pub(crate) type_formats: Vec<TypeFormat>,
+    /// Segments of the argument names reported as secrets, unless the defaults apply.
+    pub(crate) sensitive: Option<Vec<String>>,
+    /// Shared between the copies handed down to the enclosed items.
+    usage: Rc<Usage>,
+}
+
+/// What the arguments have been applied to, for reporting the options that have no effect.
+#[derive(Default)]
+struct Usage {
+    /// Number of functions the arguments have been considered for.
+    functions: Cell<usize>,
+    /// Names of the arguments, and `res`, of the traced functions.
+    names: RefCell<BTreeSet<String>>,
+    /// Paths of the functions relative to the annotated item.
+    items: RefCell<BTreeSet<String>>,
+    /// Number of functions whose returned iterator or stream is traced by `items`.
+    iterators: Cell<usize>,
+    /// Number of functions whose returned closure is traced by `returned_closure`.
+    returned_closures: Cell<usize>,
+    /// Number of functions whose `&mut` arguments are logged at exit by `mut_after`.
+    mut_refs: Cell<usize>,
+    /// Number of methods whose receiver is logged by `receiver`.
+    receivers: Cell<usize>,
+    /// Rules of `fmt(...)` that applied to a value.
+    type_formats: RefCell<BTreeSet<String>>,
+}
+
+/// How `receiver` logs `self` on entry.
+#[derive(Clone, Default)]
+pub(crate) struct Receiver {
+    /// `self` formatted with `Debug`.
+    pub(crate) debug: bool,
+    /// The address of the object.
+    pub(crate) addr: bool,
+    /// The name of the concrete type of the object.
+    pub(crate) type_: bool,
+    /// Expressions of `self`, e.g., `self.id`, along with their source.
+    pub(crate) projections: Vec<(String, syn::Expr)>,
+}
+
+/// What `mut_after` logs at exit besides the `&mut` arguments.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct MutAfter {
+    /// `&mut self` is logged as well.
+    pub(crate) receiver: bool,
+    /// Only the fields whose `Debug` representation changed are logged.
+    pub(crate) diff: bool,
+}
+
+/// How a value named by `<name> = ...` is logged.
+#[derive(Clone)]
+pub(crate) enum ArgFormat {
+    /// `<name> = "{:x}"`, a format string for the value.
+    Format(String),
+    /// `<name> = with(path::to::fmt_fn)`, a function taking the value by reference and a formatter.
+    With(syn::Path),
+    /// `<name> = expr(<name>.len())`, an expression logged with `Debug` in place of the value.
+    Expr(syn::Expr),
+    /// `<name> = hex`, one of the built-in formats.
+    Builtin(Builtin),
+}
+
+/// The built-in formats, usable for any value and as `default` for all of them.
+#[derive(Clone, Copy)]
+pub(crate) enum Builtin {
+    /// Bytes as hexadecimal digits, integers as `0x...`.
+    Hex,
+    /// The number of elements of a collection, or of bytes of a string.
+    Len,
+    /// The `Debug` output cut after the given number of bytes.
+    Truncate(usize),
+    /// A short hash of the `Debug` output, stable across runs.
+    Hash,
+    /// The address pointed to by a reference or smart pointer, or that of the value.
+    Addr,
+    /// `<redacted>` in place of the value, or with `redact(hash)` a hash salted per process.
+    Redact { hashed: bool },
+}
+
+const BUILTINS: &[&str] = &["hex", "len", "truncate", "hash", "addr", "redact"];
+
+/// An argument of `#[trace]` as written.
+pub(crate) enum RawArg {
+    Meta(syn::NestedMeta),
+    /// `<name> = with(...)`, `<name> = expr(...)` or a built-in format, which are not meta items.
+    Formatter(proc_macro2::Ident, ArgFormat),
+    /// `fmt(Type = <formatting>, ...)`, whose entries are not meta items.
+    TypeFormats(proc_macro2::Span, Vec<TypeFormat>),
+}
+
+impl Parse for RawArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+        let is_formatter = fork.call(proc_macro2::Ident::parse_any).is_ok()
+            && fork.parse::<syn::Token![=]>().is_ok()
+            && fork.parse::<proc_macro2::Ident>().is_ok_and(|kind| {
+                (kind == "with" || kind == "expr") && fork.peek(syn::token::Paren)
+                    || BUILTINS.iter().any(|builtin| kind == builtin)
+            });
+
+        let fork = input.fork();
+        let is_type_formats = fork
+            .parse::<proc_macro2::Ident>()
+            .is_ok_and(|ident| ident == "fmt")
+            && fork.peek(syn::token::Paren);
+
+        if is_type_formats {
+            let ident = input.parse::<proc_macro2::Ident>()?;
+            let content;
+            syn::parenthesized!(content in input);
+            let rules = content.parse_terminated::<_, syn::Token![,]>(TypeFormat::parse)?;
+            return Ok(RawArg::TypeFormats(
+                ident.span(),
+                rules.into_iter().collect(),
+            ));
+        }
+
+        if !is_formatter {
+            return input.parse().map(RawArg::Meta);
+        }
+
+        let name = input.call(proc_macro2::Ident::parse_any)?;
+        input.parse::<syn::Token![=]>()?;
+        let format = parse_format(input)?;
+
+        Ok(RawArg::Formatter(name, format))
+    }
+}
+
+/// Parses what follows `<name> =` other than a format string:
+/// `with(...)`, `expr(...)` or a built-in format.
+fn parse_format(input: ParseStream) -> syn::Result<ArgFormat> {
+    let kind = input.parse::<proc_macro2::Ident>()?;
+
+    let format = match kind.to_string().as_str() {
+        "hex" => ArgFormat::Builtin(Builtin::Hex),
+        "len" => ArgFormat::Builtin(Builtin::Len),
+        "hash" => ArgFormat::Builtin(Builtin::Hash),
+        "addr" => ArgFormat::Builtin(Builtin::Addr),
+        "redact" if input.peek(syn::token::Paren) => {
+            let content;
+            syn::parenthesized!(content in input);
+            let hash = content.parse::<proc_macro2::Ident>()?;
+            if hash != "hash" {
+                return Err(syn::Error::new(hash.span(), "expected `hash`"));
+            }
+            ArgFormat::Builtin(Builtin::Redact { hashed: true })
+        }
+        "redact" => ArgFormat::Builtin(Builtin::Redact { hashed: false }),
+        "truncate" => {
+            let content;
+            syn::parenthesized!(content in input);
+            let limit = content.parse::<syn::LitInt>()?;
+            ArgFormat::Builtin(Builtin::Truncate(limit.value() as usize))
+        }
+        kind => {
+            let content;
+            syn::parenthesized!(content in input);
+            if kind == "with" {
+                ArgFormat::With(content.parse()?)
+            } else {
+                ArgFormat::Expr(content.parse()?)
+            }
+        }
+    };
+
+    Ok(format)
+}
+
+/// A rule of `fmt(...)`, e.g., `Vec<u8> = hex`, formatting the values of a type whatever their name.
+#[derive(Clone)]
+pub(crate) struct TypeFormat {
+    pub(crate) ty: syn::Type,
+    /// The type as matched against those of the values, without whitespace.
+    key: String,
+    pub(crate) format: ArgFormat,
+}
+
+impl Parse for TypeFormat {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty = input.parse::<syn::Type>()?;
+        input.parse::<syn::Token![=]>()?;
+        let format = if input.peek(syn::LitStr) {
+            ArgFormat::Format(input.parse::<syn::LitStr>()?.value())
+        } else {
+            parse_format(input)?
+        };
+        if let ArgFormat::Expr(ref expr) = format {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "`expr(...)` refers to a value by its name, it cannot format a type",
+            ));
+        }
+
+        Ok(TypeFormat {
+            key: type_key(&ty),
+            ty,
+            format,
+        })
+    }
+}
+
+impl TypeFormat {
+    /// Decides whether the rule applies to a value of type `ty`, e.g., `Uuid` to `uuid::Uuid`.
+    fn matches(&self, ty: &str) -> bool {
+        ty == self.key
+            || ty
+                .strip_suffix(self.key.as_str())
+                .is_some_and(|path| path.ends_with("::"))
+    }
+}
+
+/// The type as written, without whitespace.
+pub(crate) fn type_key(ty: &syn::Type) -> String {
+    ty.into_token_stream()
+        .to_string()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+/// Parses the arguments of `#[trace(...)]`, within the parentheses.
+pub(crate) fn parse_raw_args(input: ParseStream) -> syn::Result<Vec<RawArg>> {
+    let args = input.parse_terminated::<_, syn::Token![,]>(RawArg::parse)?;
+    Ok(args.into_iter().collect())
+}
+
+#[derive(Clone)]
+pub(crate) enum Filter {
+    None,
+    Enable(Vec<Pattern>),
+    Disable(Vec<Pattern>),
+}
+
+impl Filter {
+    /// Decides whether an argument, or `res`, named `name` is filtered out.
+    pub(crate) fn excludes(&self, name: &str) -> bool {
+        // Every pattern is matched, so that its usage is recorded.
+        match *self {
+            Filter::None => false,
+            Filter::Enable(ref patterns) => {
+                patterns.iter().filter(|p| p.matches(name)).count() == 0
+            }
+            Filter::Disable(ref patterns) => {
+                patterns.iter().filter(|p| p.matches(name)).count() > 0
+            }
+        }
+    }
+
+    /// Decides whether a function at `path`, relative to the item the filter is attached to,
+    /// is filtered out.
+    pub(crate) fn excludes_path(&self, path: &[Segment]) -> bool {
+        match *self {
+            Filter::None => false,
+            Filter::Enable(ref patterns) => {
+                patterns.iter().filter(|p| p.matches_path(path)).count() == 0
+            }
+            Filter::Disable(ref patterns) => {
+                patterns.iter().filter(|p| p.matches_path(path)).count() > 0
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Prefix(Option<String>);
+
+impl Prefix {
+    const DEFAULT_ENTER: &'static str = ">>>";
+    const DEFAULT_EXIT: &'static str = "<<<";
+
+    pub(crate) fn enter(&self) -> String {
+        format!(
+            "{} {}",
+            Self::DEFAULT_ENTER,
+            self.0.as_deref().unwrap_or("")
+        )
+    }
+
+    pub(crate) fn exit(&self) -> String {
+        format!("{} {}", Self::DEFAULT_EXIT, self.0.as_deref().unwrap_or(""))
+    }
+}
+
+impl<'a> FromIterator<&'a Prefix> for Prefix {
+    fn from_iter<T: IntoIterator<Item = &'a Prefix>>(iter: T) -> Self {
+        let mut segments = Vec::new();
+
+        for item in iter {
+            if let Some(value) = item.0.as_ref() {
+                segments.push(value.clone());
+            }
+        }
+
+        Prefix(if segments.is_empty() {
+            None
+        } else {
+            Some(segments.join(""))
+        })
+    }
+}
+
+const DEFAULT_PAUSE: bool = false;
+const DEFAULT_PRETTY: bool = false;
+const DEFAULT_ONLY_PUB: bool = false;
+const DEFAULT_SKIP_TRIVIAL: bool = false;
+const DEFAULT_OFF: bool = false;
+const DEFAULT_INHERIT: bool = true;
+const DEFAULT_NESTED: bool = false;
+const DEFAULT_CLOSURES: bool = false;
+const DEFAULT_TYPES: bool = false;
+/// Number of items of a returned iterator or stream logged by `items`.
+const DEFAULT_ITEMS_LIMIT: usize = 10;
+
+/// Names of all options, for suggestions on misspelled ones.
+const OPTIONS: &[&str] = &[
+    "prefix_enter",
+    "prefix_exit",
+    "prefix",
+    "enable",
+    "disable",
+    "pause",
+    "pretty",
+    "only_pub",
+    "skip_trivial",
+    "skip_trait_impls",
+    "off",
+    "inherit",
+    "nested",
+    "closures",
+    "items",
+    "returned_closure",
+    "mut_after",
+    "receiver",
+    "types",
+    "default",
+    "fmt",
+    "redact",
+    "sensitive",
+];
+
+impl Args {
+    /// Decides whether a function at `path`, relative to the item the arguments are attached to,
+    /// is filtered out by `off`, `enable`, `disable`, `only_pub`, `skip_trivial` or `skip_trait_impls`.
+    pub(crate) fn excludes_fn(&self, path: &[Segment], is_pub: bool, is_trivial: bool) -> bool {
+        let in_skipped_trait_impl = path.iter().any(|segment| match *segment {
+            Segment::Impl {
+                trait_: Some(ref trait_),
+                ..
+            } => {
+                self.skip_trait_impls
+                    .iter()
+                    .filter(|p| p.matches(trait_))
+                    .count()
+                    > 0
+            }
+            _ => false,
+        });
+
+        self.off
+            || self.filter.excludes_path(path)
+            || (self.only_pub && !is_pub)
+            || (self.skip_trivial && is_trivial)
+            || in_skipped_trait_impl
+    }
+
+    /// Records that the arguments are considered for a function at `path`,
+    /// relative to the item the arguments are attached to, if any.
+    pub(crate) fn note_function(&self, path: Option<&[Segment]>) {
+        self.usage.functions.set(self.usage.functions.get() + 1);
+        if let Some(path) = path {
+            self.usage.items.borrow_mut().insert(
+                path.iter()
+                    .map(Segment::name)
+                    .collect::<Vec<_>>()
+                    .join("::"),
+            );
+        }
+    }
+
+    /// Records the names of the arguments of a function traced with the arguments.
+    pub(crate) fn note_names<I: IntoIterator<Item = String>>(&self, names: I) {
+        self.usage.names.borrow_mut().extend(names);
+    }
+
+    /// Records that `items` of the arguments applies to a function.
+    pub(crate) fn note_iterator(&self) {
+        self.usage.iterators.set(self.usage.iterators.get() + 1);
+    }
+
+    /// Records that `returned_closure` of the arguments applies to a function.
+    pub(crate) fn note_returned_closure(&self) {
+        self.usage
+            .returned_closures
+            .set(self.usage.returned_closures.get() + 1);
+    }
+
+    /// Records that `mut_after` of the arguments applies to a function.
+    pub(crate) fn note_mut_after(&self) {
+        self.usage.mut_refs.set(self.usage.mut_refs.get() + 1);
+    }
+
+    /// Records that `receiver` of the arguments applies to a method.
+    pub(crate) fn note_receiver(&self) {
+        self.usage.receivers.set(self.usage.receivers.get() + 1);
+    }
+
+    /// Finds the rule of `fmt(...)` for values of type `ty`, as given by `type_key`, and records its usage.
+    pub(crate) fn type_format(&self, ty: &str) -> Option<&ArgFormat> {
+        let rule = self.type_formats.iter().find(|rule| rule.matches(ty))?;
+        self.usage
+            .type_formats
+            .borrow_mut()
+            .insert(rule.key.clone());
+        Some(&rule.format)
+    }
+
+    /// Reports the argument formats, the filter entries and the options applying to functions
+    /// of some kinds only, e.g., `items`, that have no effect on any function;
+    /// `on_fn` tells whether the arguments are attached to a function rather than to a `mod` or an `impl`.
+    pub(crate) fn unused(&self, on_fn: bool) -> Vec<syn::Error> {
+        let mut errors = Vec::new();
+
+        if self.off || self.usage.functions.get() == 0 {
+            return errors;
+        }
+
+        let names = self.usage.names.borrow();
+        let items = self.usage.items.borrow();
+
+        // Formats can only be checked against the functions actually traced.
+        if !names.is_empty() {
+            let mut formats = self
+                .args_format
+                .keys()
+                .filter(|ident| !names.contains(&ident.to_string()))
+                .collect::<Vec<_>>();
+            formats.sort_by_key(|ident| ident.to_string());
+
+            errors.extend(formats.into_iter().map(|ident| {
+                let name = ident.to_string();
+                let candidates = names
+                    .iter()
+                    .map(String::as_str)
+                    .chain(OPTIONS.iter().cloned());
+
+                syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "no traced function has an argument `{}`{}",
+                        name,
+                        did_you_mean(&name, candidates)
+                    ),
+                )
+            }));
+        }
+
+        let type_formats = self.usage.type_formats.borrow();
+        errors.extend(
+            self.type_formats
+                .iter()
+                .filter(|rule| !type_formats.contains(&rule.key))
+                .map(|rule| {
+                    syn::Error::new_spanned(
+                        &rule.ty,
+                        format!(
+                            "no traced function has an argument or a result of type `{}`",
+                            rule.key
+                        ),
+                    )
+                }),
+        );
+
+        let (arg_name, patterns) = match self.filter {
+            Filter::None => ("", &[][..]),
+            Filter::Enable(ref patterns) => ("enable", &patterns[..]),
+            Filter::Disable(ref patterns) => ("disable", &patterns[..]),
+        };
+        let (target, candidates) = if on_fn {
+            ("argument", &*names)
+        } else {
+            ("function", &*items)
+        };
+
+        errors.extend(
+            patterns
+                .iter()
+                .filter(|pattern| !pattern.is_used())
+                .map(|pattern| {
+                    syn::Error::new(
+                        pattern.span(),
+                        format!(
+                            "`{}` in `{}` matches no {}{}",
+                            pattern.source(),
+                            arg_name,
+                            target,
+                            did_you_mean(pattern.source(), candidates.iter().map(String::as_str))
+                        ),
+                    )
+                }),
+        );
+
+        if let Some((span, _)) = self.items {
+            if self.usage.iterators.get() == 0 {
+                errors.push(syn::Error::new(
+                    span,
+                    "`items` applies to no function returning an iterator or a stream",
+                ));
+            }
+        }
+
+        if let Some(span) = self.returned_closure {
+            if self.usage.returned_closures.get() == 0 {
+                errors.push(syn::Error::new(
+                    span,
+                    "`returned_closure` applies to no function returning a closure",
+                ));
+            }
+        }
+
+        if let Some((span, _)) = self.mut_after {
+            if self.usage.mut_refs.get() == 0 {
+                errors.push(syn::Error::new(
+                    span,
+                    "`mut_after` applies to no function taking a `&mut` argument",
+                ));
+            }
+        }
+
+        if let Some((span, _)) = self.receiver {
+            if self.usage.receivers.get() == 0 {
+                errors.push(syn::Error::new(span, "`receiver` applies to no method"));
+            }
+        }
+
+        if !on_fn {
+            errors.extend(
+                self.skip_trait_impls
+                    .iter()
+                    .filter(|pattern| !pattern.is_used())
+                    .map(|pattern| {
+                        syn::Error::new(
+                            pattern.span(),
+                            format!(
+                                "`{}` in `skip_trait_impls` matches no trait implementation",
+                                pattern.source()
+                            ),
+                        )
+                    }),
+            );
+        }
+
+        errors
+    }
+
+    /// Drops the options selecting functions, which do not apply to explicitly annotated items.
+    pub(crate) fn without_item_filters(mut self) -> Self {
+        self.off = DEFAULT_OFF;
+        self.filter = Filter::None;
+        self.only_pub = DEFAULT_ONLY_PUB;
+        self.skip_trivial = DEFAULT_SKIP_TRIVIAL;
+        self.skip_trait_impls = Vec::new();
+        self
+    }
+
+    pub(crate) fn from_raw_args(raw_args: Vec<RawArg>) -> Result<Self, Vec<syn::Error>> {
+        // Different types of arguments accepted by `#[trace]`;
+        // spans are needed for friendly error reporting of duplicate arguments
+        #[allow(clippy::enum_variant_names)]
+        enum Arg {
+            PrefixEnter(proc_macro2::Span, String),
+            PrefixExit(proc_macro2::Span, String),
+            Prefix(proc_macro2::Span, String),
+            Enable(proc_macro2::Span, Vec<Pattern>),
+            Disable(proc_macro2::Span, Vec<Pattern>),
+            Pause(proc_macro2::Span, bool),
+            Pretty(proc_macro2::Span, bool),
+            OnlyPub(proc_macro2::Span, bool),
+            SkipTrivial(proc_macro2::Span, bool),
+            SkipTraitImpls(proc_macro2::Span, Vec<Pattern>),
+            Off(proc_macro2::Span, bool),
+            Inherit(proc_macro2::Span, bool),
+            Nested(proc_macro2::Span, bool),
+            Closures(proc_macro2::Span, bool),
+            Items(proc_macro2::Span, usize),
+            ReturnedClosure(proc_macro2::Span, bool),
+            MutAfter(proc_macro2::Span, MutAfter),
+            Receiver(proc_macro2::Span, Receiver),
+            Types(proc_macro2::Span, bool),
+            Default(proc_macro2::Span, Builtin),
+            Redact(proc_macro2::Span, Vec<proc_macro2::Ident>),
+            Sensitive(proc_macro2::Span, Vec<String>),
+            ArgFormat(proc_macro2::Span, (proc_macro2::Ident, ArgFormat)),
+            TypeFormats(proc_macro2::Span, Vec<TypeFormat>),
+        }
+
+        // Parse arguments
+        let args_res = raw_args.into_iter().map(|raw_arg| match raw_arg {
+            RawArg::Formatter(ref ident, ArgFormat::Builtin(builtin)) if ident == "default" => {
+                Ok(Arg::Default(ident.span(), builtin))
+            }
+            RawArg::Formatter(ref ident, _) if ident == "default" => Err(vec![
+                syn::Error::new_spanned(ident, "`default` must be a built-in format, e.g., `default = truncate(80)`"),
+            ]),
+            RawArg::Formatter(ident, format) => Ok(Arg::ArgFormat(ident.span(), (ident, format))),
+            RawArg::TypeFormats(span, rules) => Ok(Arg::TypeFormats(span, rules)),
+            RawArg::Meta(syn::NestedMeta::Meta(ref meta)) => {
+                enum ArgName {
+                    PrefixEnter,
+                    PrefixExit,
+                    Prefix,
+                    Enable,
+                    Disable,
+                    Pause,
+                    Pretty,
+                    OnlyPub,
+                    SkipTrivial,
+                    SkipTraitImpls,
+                    Off,
+                    Inherit,
+                    Nested,
+                    Closures,
+                    Items,
+                    ReturnedClosure,
+                    MutAfter,
+                    Receiver,
+                    Types,
+                    Default,
+                    Redact,
+                    Sensitive,
+                    ArgFormat,
+                }
+
+                let ident = meta.name();
+                let arg_name = match ident.to_string().as_str() {
+                    "prefix_enter" => ArgName::PrefixEnter,
+                    "prefix_exit" => ArgName::PrefixExit,
+                    "prefix" => ArgName::Prefix,
+                    "enable" => ArgName::Enable,
+                    "disable" => ArgName::Disable,
+                    "pause" => ArgName::Pause,
+                    "pretty" => ArgName::Pretty,
+                    "only_pub" => ArgName::OnlyPub,
+                    "skip_trivial" => ArgName::SkipTrivial,
+                    "skip_trait_impls" => ArgName::SkipTraitImpls,
+                    "off" => ArgName::Off,
+                    "inherit" => ArgName::Inherit,
+                    "nested" => ArgName::Nested,
+                    "closures" => ArgName::Closures,
+                    "items" => ArgName::Items,
+                    "returned_closure" => ArgName::ReturnedClosure,
+                    "mut_after" => ArgName::MutAfter,
+                    "receiver" => ArgName::Receiver,
+                    "types" => ArgName::Types,
+                    "default" => ArgName::Default,
+                    "redact" => ArgName::Redact,
+                    "sensitive" => ArgName::Sensitive,
+                    _ => ArgName::ArgFormat,
+                };
+
+                let prefix_enter_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`prefix_enter` requires a string value",
+                    )]
+                };
+                let prefix_exit_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`prefix_exit` requires a string value",
+                    )]
+                };
+                let prefix_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`prefix` requires a string value",
+                    )]
+                };
+                let enable_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`enable` requires a list of meta words or string patterns",
+                    )]
+                };
+                let disable_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`disable` requires a list of meta words or string patterns",
+                    )]
+                };
+                let pause_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`pause` must be a meta word",
+                    )]
+                };
+                let pretty_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`pretty` must be a meta word",
+                    )]
+                };
+                let only_pub_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`only_pub` must be a meta word",
+                    )]
+                };
+                let skip_trivial_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`skip_trivial` must be a meta word",
+                    )]
+                };
+                let skip_trait_impls_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`skip_trait_impls` requires a list of meta words or string patterns",
+                    )]
+                };
+                let off_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`off` must be a meta word",
+                    )]
+                };
+                let inherit_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`inherit` requires a boolean value",
+                    )]
+                };
+                let nested_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`nested` must be a meta word",
+                    )]
+                };
+                let closures_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`closures` must be a meta word",
+                    )]
+                };
+                let items_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`items` must be a meta word or have an integer value",
+                    )]
+                };
+                let returned_closure_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`returned_closure` must be a meta word",
+                    )]
+                };
+                let mut_after_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`mut_after` must be a meta word or a list of `self` and `diff`",
+                    )]
+                };
+                let receiver_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`receiver` must be a meta word or a list of `debug`, `addr`, `type` and string projections",
+                    )]
+                };
+                let types_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`types` must be a meta word",
+                    )]
+                };
+                let default_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`default` must be a built-in format, e.g., `default = truncate(80)`",
+                    )]
+                };
+                let redact_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`redact` must list the arguments to redact, e.g., `redact(password, res)`",
+                    )]
+                };
+                let sensitive_type_error = || {
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        "`sensitive` must list the name segments of secrets, e.g., `sensitive(password, token)`",
+                    )]
+                };
+                let unknown_option_error = || {
+                    let name = ident.to_string();
+
+                    vec![syn::Error::new_spanned(
+                        ident.clone(),
+                        format!(
+                            "unknown option `{}`{}",
+                            name,
+                            did_you_mean(&name, OPTIONS.iter().cloned())
+                        ),
+                    )]
+                };
+
+                match *meta {
+                    syn::Meta::Word(_) => match arg_name {
+                        ArgName::Pause => Ok(Arg::Pause(meta.span(), true)),
+                        ArgName::Pretty => Ok(Arg::Pretty(meta.span(), true)),
+
+                        ArgName::PrefixEnter => Err(prefix_enter_type_error()),
+                        ArgName::PrefixExit => Err(prefix_exit_type_error()),
+                        ArgName::Prefix => Err(prefix_type_error()),
+                        ArgName::Enable => Err(enable_type_error()),
+                        ArgName::Disable => Err(disable_type_error()),
+                        ArgName::OnlyPub => Ok(Arg::OnlyPub(meta.span(), true)),
+                        ArgName::SkipTrivial => Ok(Arg::SkipTrivial(meta.span(), true)),
+                        ArgName::SkipTraitImpls => Err(skip_trait_impls_type_error()),
+                        ArgName::Off => Ok(Arg::Off(meta.span(), true)),
+                        ArgName::Inherit => Err(inherit_type_error()),
+                        ArgName::Nested => Ok(Arg::Nested(meta.span(), true)),
+                        ArgName::Closures => Ok(Arg::Closures(meta.span(), true)),
+                        ArgName::Items => Ok(Arg::Items(meta.span(), DEFAULT_ITEMS_LIMIT)),
+                        ArgName::ReturnedClosure => Ok(Arg::ReturnedClosure(meta.span(), true)),
+                        ArgName::MutAfter => Ok(Arg::MutAfter(meta.span(), MutAfter::default())),
+                        ArgName::Receiver => Ok(Arg::Receiver(meta.span(), Receiver { debug: true, ..Receiver::default() })),
+                        ArgName::Types => Ok(Arg::Types(meta.span(), true)),
+                        ArgName::Default => Err(default_type_error()),
+                        ArgName::Redact => Err(redact_type_error()),
+                        ArgName::Sensitive => Err(sensitive_type_error()),
+                        ArgName::ArgFormat => Err(unknown_option_error()),
+                    },
+                    syn::Meta::List(syn::MetaList { ref nested, .. }) => match arg_name {
+                        ArgName::Enable => parse_patterns(nested, "enable")
+                            .map(|patterns| Arg::Enable(meta.span(), patterns)),
+                        ArgName::Disable => parse_patterns(nested, "disable")
+                            .map(|patterns| Arg::Disable(meta.span(), patterns)),
+
+                        ArgName::PrefixEnter => Err(prefix_enter_type_error()),
+                        ArgName::PrefixExit => Err(prefix_exit_type_error()),
+                        ArgName::Prefix => Err(prefix_type_error()),
+                        ArgName::ArgFormat => Err(unknown_option_error()),
+                        ArgName::Pause => Err(pause_type_error()),
+                        ArgName::Pretty => Err(pretty_type_error()),
+                        ArgName::OnlyPub => Err(only_pub_type_error()),
+                        ArgName::SkipTrivial => Err(skip_trivial_type_error()),
+                        ArgName::SkipTraitImpls => parse_patterns(nested, "skip_trait_impls")
+                            .map(|patterns| Arg::SkipTraitImpls(meta.span(), patterns)),
+                        ArgName::Off => Err(off_type_error()),
+                        ArgName::Inherit => Err(inherit_type_error()),
+                        ArgName::Nested => Err(nested_type_error()),
+                        ArgName::Closures => Err(closures_type_error()),
+                        ArgName::Items => Err(items_type_error()),
+                        ArgName::ReturnedClosure => Err(returned_closure_type_error()),
+                        ArgName::MutAfter => parse_mut_after(nested)
+                            .map(|mut_after| Arg::MutAfter(meta.span(), mut_after)),
+                        ArgName::Receiver => parse_receiver(nested).map(|receiver| Arg::Receiver(meta.span(), receiver)),
+                        ArgName::Types => Err(types_type_error()),
+                        ArgName::Default => Err(default_type_error()),
+                        ArgName::Redact => {
+                            parse_redact(nested).map(|idents| Arg::Redact(meta.span(), idents))
+                        }
+                        ArgName::Sensitive => parse_sensitive(nested)
+                            .map(|segments| Arg::Sensitive(meta.span(), segments)),
+                    },
+                    syn::Meta::NameValue(syn::MetaNameValue {
+                        ref ident, ref lit, ..
+                    }) => match arg_name {
+                        ArgName::PrefixEnter => match *lit {
+                            syn::Lit::Str(ref lit_str) => {
+                                Ok(Arg::PrefixEnter(meta.span(), lit_str.value()))
+                            }
+                            _ => Err(vec![syn::Error::new_spanned(
+                                lit,
+                                "`prefix_enter` must have a string value",
+                            )]),
+                        },
+                        ArgName::PrefixExit => match *lit {
+                            syn::Lit::Str(ref lit_str) => {
+                                Ok(Arg::PrefixExit(meta.span(), lit_str.value()))
+                            }
+                            _ => Err(vec![syn::Error::new_spanned(
+                                lit,
+                                "`prefix_exit` must have a string value",
+                            )]),
+                        },
+                        ArgName::Prefix => match *lit {
+                            syn::Lit::Str(ref lit_str) => {
+                                Ok(Arg::Prefix(meta.span(), lit_str.value()))
+                            }
+                            _ => Err(vec![syn::Error::new_spanned(
+                                lit,
+                                "`prefix` must have a string value",
+                            )]),
+                        },
+                        ArgName::ArgFormat => match *lit {
+                            syn::Lit::Str(ref lit_str) => Ok(Arg::ArgFormat(
+                                meta.span(),
+                                (ident.clone(), ArgFormat::Format(lit_str.value())),
+                            )),
+                            _ => Err(vec![syn::Error::new_spanned(
+                                lit,
+                                "value formatter must have a string value",
+                            )]),
+                        },
+
+                        ArgName::Enable => Err(enable_type_error()),
+                        ArgName::Disable => Err(disable_type_error()),
+                        ArgName::Pause => Err(pause_type_error()),
+                        ArgName::Pretty => Err(pretty_type_error()),
+                        ArgName::OnlyPub => Err(only_pub_type_error()),
+                        ArgName::SkipTrivial => Err(skip_trivial_type_error()),
+                        ArgName::SkipTraitImpls => Err(skip_trait_impls_type_error()),
+                        ArgName::Off => Err(off_type_error()),
+                        ArgName::Inherit => match *lit {
+                            syn::Lit::Bool(ref lit_bool) => {
+                                Ok(Arg::Inherit(meta.span(), lit_bool.value))
+                            }
+                            _ => Err(vec![syn::Error::new_spanned(
+                                lit,
+                                "`inherit` must have a boolean value",
+                            )]),
+                        },
+                        ArgName::Nested => Err(nested_type_error()),
+                        ArgName::Closures => Err(closures_type_error()),
+                        ArgName::Items => match *lit {
+                            syn::Lit::Int(ref lit_int) => {
+                                Ok(Arg::Items(meta.span(), lit_int.value() as usize))
+                            }
+                            _ => Err(vec![syn::Error::new_spanned(
+                                lit,
+                                "`items` must have an integer value",
+                            )]),
+                        },
+                        ArgName::ReturnedClosure => Err(returned_closure_type_error()),
+                        ArgName::MutAfter => Err(mut_after_type_error()),
+                        ArgName::Receiver => Err(receiver_type_error()),
+                        ArgName::Types => Err(types_type_error()),
+                        ArgName::Default => Err(default_type_error()),
+                        ArgName::Redact => Err(redact_type_error()),
+                        ArgName::Sensitive => Err(sensitive_type_error()),
+                    },
+                }
+            }
+            RawArg::Meta(ref nested_meta @ syn::NestedMeta::Literal(_)) => {
+                Err(vec![syn::Error::new_spanned(
+                    nested_meta,
+                    "literal attribute not allowed",
+                )])
+            }
+        });
+
+        let mut prefix_enter_args = Vec::new();
+        let mut prefix_exit_args = Vec::new();
+        let mut prefix_args = Vec::new();
+        let mut enable_args = Vec::new();
+        let mut disable_args = Vec::new();
+        let mut pause_args = Vec::new();
+        let mut pretty_args = Vec::new();
+        let mut only_pub_args = Vec::new();
+        let mut skip_trivial_args = Vec::new();
+        let mut skip_trait_impls_args = Vec::new();
+        let mut off_args = Vec::new();
+        let mut inherit_args = Vec::new();
+        let mut nested_args = Vec::new();
+        let mut closures_args = Vec::new();
+        let mut items_args = Vec::new();
+        let mut returned_closure_args = Vec::new();
+        let mut mut_after_args = Vec::new();
+        let mut receiver_args = Vec::new();
+        let mut types_args = Vec::new();
+        let mut default_args = Vec::new();
+        let mut redact_args = Vec::new();
+        let mut sensitive_args = Vec::new();
+        let mut arg_format_args = HashMap::new();
+        let mut type_formats_args = Vec::new();
+        let mut errors = Vec::new();
+
+        // Group arguments of the same type and errors
+        for arg_res in args_res {
+            match arg_res {
+                Ok(arg) => match arg {
+                    Arg::PrefixEnter(span, s) => prefix_enter_args.push((span, s)),
+                    Arg::PrefixExit(span, s) => prefix_exit_args.push((span, s)),
+                    Arg::Prefix(span, s) => prefix_args.push((span, s)),
+                    Arg::Enable(span, idents) => enable_args.push((span, idents)),
+                    Arg::Disable(span, idents) => disable_args.push((span, idents)),
+                    Arg::Pause(span, b) => pause_args.push((span, b)),
+                    Arg::Pretty(span, b) => pretty_args.push((span, b)),
+                    Arg::OnlyPub(span, x) => only_pub_args.push((span, x)),
+                    Arg::SkipTrivial(span, x) => skip_trivial_args.push((span, x)),
+                    Arg::SkipTraitImpls(span, x) => skip_trait_impls_args.push((span, x)),
+                    Arg::Off(span, x) => off_args.push((span, x)),
+                    Arg::Inherit(span, x) => inherit_args.push((span, x)),
+                    Arg::Nested(span, x) => nested_args.push((span, x)),
+                    Arg::Closures(span, x) => closures_args.push((span, x)),
+                    Arg::Items(span, x) => items_args.push((span, x)),
+                    Arg::ReturnedClosure(span, x) => returned_closure_args.push((span, x)),
+                    Arg::MutAfter(span, x) => mut_after_args.push((span, x)),
+                    Arg::Receiver(span, x) => receiver_args.push((span, x)),
+                    Arg::Types(span, x) => types_args.push((span, x)),
+                    Arg::Default(span, x) => default_args.push((span, x)),
+                    Arg::TypeFormats(span, rules) => type_formats_args.push((span, rules)),
+                    Arg::Redact(span, x) => redact_args.push((span, x)),
+                    Arg::Sensitive(span, x) => sensitive_args.push((span, x)),
+                    Arg::ArgFormat(span, (ident, format)) => {
+                        #[allow(clippy::map_entry)]
+                        if !arg_format_args.contains_key(&ident) {
+                            arg_format_args.insert(ident, format);
+                        } else {
+                            errors.push(syn::Error::new(
+                                span,
+                                format!("duplicate formatting for `{}`", ident),
+                            ))
+                        }
+                    }
+                },
+                Err(es) => errors.extend(es),
+            }
+        }
+
+        // `redact(a, b)` stands for `a = redact, b = redact`.
+        for ident in redact_args.iter().flat_map(|(_, idents)| idents) {
+            #[allow(clippy::map_entry)]
+            if !arg_format_args.contains_key(ident) {
+                arg_format_args.insert(
+                    ident.clone(),
+                    ArgFormat::Builtin(Builtin::Redact { hashed: false }),
+                );
+            } else {
+                errors.push(syn::Error::new(
+                    ident.span(),
+                    format!("duplicate formatting for `{}`", ident),
+                ))
+            }
+        }
+
+        // Report duplicates
+        if prefix_enter_args.len() >= 2 {
+            errors.extend(
+                prefix_enter_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `prefix_enter`")),
+            );
+        }
+        if prefix_exit_args.len() >= 2 {
+            errors.extend(
+                prefix_exit_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `prefix_exit`")),
+            );
+        }
+        if prefix_args.len() >= 2 {
+            errors.extend(
+                prefix_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `prefix`")),
+            );
+        }
+        if enable_args.len() >= 2 {
+            errors.extend(
+                enable_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `enable`")),
+            );
+        }
+        if disable_args.len() >= 2 {
+            errors.extend(
+                disable_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `disable`")),
+            );
+        }
+        if pause_args.len() >= 2 {
+            errors.extend(
+                pause_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `pause`")),
+            );
+        }
+        if pretty_args.len() >= 2 {
+            errors.extend(
+                pretty_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `pretty`")),
+            );
+        }
+
+        if only_pub_args.len() >= 2 {
+            errors.extend(
+                only_pub_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `only_pub`")),
+            );
+        }
+        if skip_trivial_args.len() >= 2 {
+            errors.extend(
+                skip_trivial_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `skip_trivial`")),
+            );
+        }
+        if skip_trait_impls_args.len() >= 2 {
+            errors.extend(
+                skip_trait_impls_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `skip_trait_impls`")),
+            );
+        }
+        if off_args.len() >= 2 {
+            errors.extend(
+                off_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `off`")),
+            );
+        }
+        if inherit_args.len() >= 2 {
+            errors.extend(
+                inherit_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `inherit`")),
+            );
+        }
+        if nested_args.len() >= 2 {
+            errors.extend(
+                nested_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `nested`")),
+            );
+        }
+        if closures_args.len() >= 2 {
+            errors.extend(
+                closures_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `closures`")),
+            );
+        }
+        if items_args.len() >= 2 {
+            errors.extend(
+                items_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `items`")),
+            );
+        }
+        if returned_closure_args.len() >= 2 {
+            errors.extend(
+                returned_closure_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `returned_closure`")),
+            );
+        }
+        if mut_after_args.len() >= 2 {
+            errors.extend(
+                mut_after_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `mut_after`")),
+            );
+        }
+        if receiver_args.len() >= 2 {
+            errors.extend(
+                receiver_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `receiver`")),
+            );
+        }
+        if types_args.len() >= 2 {
+            errors.extend(
+                types_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `types`")),
+            );
+        }
+        if default_args.len() >= 2 {
+            errors.extend(
+                default_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `default`")),
+            );
+        }
+        if type_formats_args.len() >= 2 {
+            errors.extend(
+                type_formats_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `fmt`")),
+            );
+        }
+        let mut type_keys = BTreeSet::new();
+        for rule in type_formats_args.iter().flat_map(|(_, rules)| rules) {
+            if !type_keys.insert(&rule.key) {
+                errors.push(syn::Error::new_spanned(
+                    &rule.ty,
+                    format!("duplicate formatting for type `{}`", rule.key),
+                ));
+            }
+        }
+        if redact_args.len() >= 2 {
+            errors.extend(
+                redact_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `redact`")),
+            );
+        }
+        if sensitive_args.len() >= 2 {
+            errors.extend(
+                sensitive_args
+                    .iter()
+                    .map(|(span, _)| syn::Error::new(*span, "duplicate `sensitive`")),
+            );
+        }
+        // Report the presence of mutually exclusive arguments
+        if !enable_args.is_empty() && !disable_args.is_empty() {
+            errors.push(syn::Error::new(
+                enable_args[0].0,
+                "cannot have both `enable` and `disable`",
+            ));
+            errors.push(syn::Error::new(
+                disable_args[0].0,
+                "cannot have both `enable` and `disable`",
+            ));
+        }
+
+        // No other prefixes are allowed if `prefix` is set.
+        if !prefix_args.is_empty() && !prefix_enter_args.is_empty() {
+            errors.push(syn::Error::new(
+                prefix_args[0].0,
+                "cannot have both `prefix` and `prefix_enter`",
+            ));
+            errors.push(syn::Error::new(
+                prefix_enter_args[0].0,
+                "cannot have both `prefix` and `prefix_enter`",
+            ));
+        }
+
+        if !prefix_args.is_empty() && !prefix_exit_args.is_empty() {
+            errors.push(syn::Error::new(
+                prefix_args[0].0,
+                "cannot have both `prefix` and `prefix_exit`",
+            ));
+            errors.push(syn::Error::new(
+                prefix_exit_args[0].0,
+                "cannot have both `prefix` and `prefix_exit`",
+            ));
+        }
+
+        if errors.is_empty() {
+            macro_rules! first_no_span {
+                ($iterable:expr) => {
+                    $iterable.into_iter().next().map(|(_, elem)| elem)
+                };
+            }
+
+            let (prefix_enter, prefix_exit) = if prefix_args.is_empty() {
+                (
+                    first_no_span!(prefix_enter_args),
+                    first_no_span!(prefix_exit_args),
+                )
+            } else {
+                let common_prefix = first_no_span!(prefix_args);
+                (common_prefix.clone(), common_prefix)
+            };
+            let prefix_enter = prefix_enter
+                .map(|prefix| Prefix(Some(prefix)))
+                .unwrap_or_else(|| Prefix(None));
+            let prefix_exit = prefix_exit
+                .map(|prefix| Prefix(Some(prefix)))
+                .unwrap_or_else(|| Prefix(None));
+
+            let filter = match (first_no_span!(enable_args), first_no_span!(disable_args)) {
+                (None, None) => Filter::None,
+                (Some(idents), None) => Filter::Enable(idents),
+                (None, Some(idents)) => Filter::Disable(idents),
+                (Some(_), Some(_)) => unreachable!(),
+            };
+            let pause = first_no_span!(pause_args).unwrap_or(DEFAULT_PAUSE);
+            let pretty = first_no_span!(pretty_args).unwrap_or(DEFAULT_PRETTY);
+            let only_pub = first_no_span!(only_pub_args).unwrap_or(DEFAULT_ONLY_PUB);
+            let skip_trivial = first_no_span!(skip_trivial_args).unwrap_or(DEFAULT_SKIP_TRIVIAL);
+            let skip_trait_impls = first_no_span!(skip_trait_impls_args).unwrap_or_default();
+            let off = first_no_span!(off_args).unwrap_or(DEFAULT_OFF);
+            let inherit = first_no_span!(inherit_args).unwrap_or(DEFAULT_INHERIT);
+            let nested = first_no_span!(nested_args).unwrap_or(DEFAULT_NESTED);
+            let closures = first_no_span!(closures_args).unwrap_or(DEFAULT_CLOSURES);
+            let items = items_args.first().cloned();
+            let returned_closure = returned_closure_args.first().map(|(span, _)| *span);
+            let mut_after = mut_after_args.first().cloned();
+            let receiver = receiver_args.first().cloned();
+            let types = first_no_span!(types_args).unwrap_or(DEFAULT_TYPES);
+            let default_format = default_args.first().map(|(_, builtin)| *builtin);
+
+            Ok(Self {
+                prefix_enter,
+                prefix_exit,
+                filter,
+                pause,
+                pretty,
+                only_pub,
+                skip_trivial,
+                skip_trait_impls,
+                off,
+                inherit,
+                nested,
+                closures,
+                items,
+                returned_closure,
+                mut_after,
+                receiver,
+                types,
+                default_format,
+                args_format: arg_format_args,
+                type_formats: first_no_span!(type_formats_args).unwrap_or_default(),
+                sensitive: first_no_span!(sensitive_args),
+                usage: Rc::new(Usage::default()),
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Parses entries of `enable` or `disable`: meta words and string patterns.
+fn parse_patterns(
+    nested: &syn::punctuated::Punctuated<syn::NestedMeta, syn::token::Comma>,
+    arg_name: &str,
+) -> Result<Vec<Pattern>, Vec<syn::Error>> {
+    let mut patterns = Vec::new();
+    let mut errors = Vec::new();
+
+    nested.iter().for_each(|nested_meta| match *nested_meta {
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) => {
+            patterns.push(Pattern::ident(word));
+        }
+        syn::NestedMeta::Literal(syn::Lit::Str(ref lit_str)) => {
+            match Pattern::parse(&lit_str.value(), lit_str.span()) {
+                Ok(pattern) => patterns.push(pattern),
+                Err(err) => errors.push(syn::Error::new_spanned(lit_str, err)),
+            }
+        }
+        _ => errors.push(syn::Error::new_spanned(
+            nested_meta,
+            format!("`{}` must contain words or string patterns only", arg_name),
+        )),
+    });
+
+    if errors.is_empty() {
+        Ok(patterns)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses the entries of `redact(...)`: names of arguments, or `res`.
+fn parse_redact(
+    nested: &syn::punctuated::Punctuated<syn::NestedMeta, syn::token::Comma>,
+) -> Result<Vec<proc_macro2::Ident>, Vec<syn::Error>> {
+    let mut idents = Vec::new();
+    let mut errors = Vec::new();
+
+    nested.iter().for_each(|nested_meta| match *nested_meta {
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) => idents.push(word.clone()),
+        _ => errors.push(syn::Error::new_spanned(
+            nested_meta,
+            "`redact` must contain argument names only",
+        )),
+    });
+
+    if errors.is_empty() {
+        Ok(idents)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses the entries of `sensitive(...)`: segments of argument names, e.g., `key` for `api_key`.
+fn parse_sensitive(
+    nested: &syn::punctuated::Punctuated<syn::NestedMeta, syn::token::Comma>,
+) -> Result<Vec<String>, Vec<syn::Error>> {
+    let mut segments = Vec::new();
+    let mut errors = Vec::new();
+
+    nested.iter().for_each(|nested_meta| match *nested_meta {
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) => {
+            segments.push(word.to_string().to_lowercase())
+        }
+        _ => errors.push(syn::Error::new_spanned(
+            nested_meta,
+            "`sensitive` must contain name segments only",
+        )),
+    });
+
+    if errors.is_empty() {
+        Ok(segments)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses the entries of `receiver(...)`: `debug`, `addr`, `type` and projections, e.g., `"self.id"`.
+fn parse_receiver(
+    nested: &syn::punctuated::Punctuated<syn::NestedMeta, syn::token::Comma>,
+) -> Result<Receiver, Vec<syn::Error>> {
+    let mut receiver = Receiver::default();
+    let mut errors = Vec::new();
+
+    nested.iter().for_each(|nested_meta| match *nested_meta {
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) if word == "debug" => {
+            receiver.debug = true;
+        }
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) if word == "addr" => {
+            receiver.addr = true;
+        }
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) if word == "type" => {
+            receiver.type_ = true;
+        }
+        syn::NestedMeta::Literal(syn::Lit::Str(ref lit_str)) => {
+            match lit_str.parse::<syn::Expr>() {
+                Ok(expr) => receiver.projections.push((lit_str.value(), expr)),
+                Err(err) => errors.push(err),
+            }
+        }
+        _ => errors.push(syn::Error::new_spanned(
+            nested_meta,
+            "`receiver` must contain `debug`, `addr`, `type` or string projections only",
+        )),
+    });
+
+    if errors.is_empty() {
+        Ok(receiver)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses the entries of `mut_after(...)`: `self` and `diff`.
+fn parse_mut_after(
+    nested: &syn::punctuated::Punctuated<syn::NestedMeta, syn::token::Comma>,
+) -> Result<MutAfter, Vec<syn::Error>> {
+    let mut mut_after = MutAfter::default();
+    let mut errors = Vec::new();
+
+    nested.iter().for_each(|nested_meta| match *nested_meta {
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) if word == "self" => {
+            mut_after.receiver = true;
+        }
+        syn::NestedMeta::Meta(syn::Meta::Word(ref word)) if word == "diff" => {
+            mut_after.diff = true;
+        }
+        _ => errors.push(syn::Error::new_spanned(
+            nested_meta,
+            "`mut_after` must contain `self` or `diff` only",
+        )),
+    });
+
+    if errors.is_empty() {
+        Ok(mut_after)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Suggests the candidate closest to a misspelled `name`, if any is close enough.
+fn did_you_mean<'a, I: IntoIterator<Item = &'a str>>(name: &str, candidates: I) -> String {
+    let max_distance = std::cmp::max(1, name.chars().count() / 3);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        // A single character name is not a misspelling of another one.
+        .filter(|(distance, _)| *distance <= max_distance && *distance < name.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| format!(", did you mean `{}`?", candidate))
+        .unwrap_or_default()
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + if ca == *cb { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = std::cmp::min(substitution, std::cmp::min(row[j], row[j + 1]) + 1);
+        }
+    }
+
+    row[b.len()]
+}
@@ -0,0 +1,96 @@
+//! Compile-time filter parsed from the `TRACE_COMPILE_FILTER` environment variable.
+//!
+//! The syntax follows the runtime `TRACE_FILTER`: a comma separated list of directives
+//! `<pattern>[=on|off]` where the longest matching pattern wins. Patterns are matched against
+//! `<crate>::[<module>::]*[<type>::]<function>`, where only the modules enclosed by the item
+//! `#[trace]` is attached to are known. Functions that are filtered out are left untouched.
+
+use crate::pattern;
+use std::cell::RefCell;
+use std::env;
+use std::rc::Rc;
+
+pub(crate) const ENV_VAR: &str = "TRACE_COMPILE_FILTER";
+
+pub(crate) struct Filter {
+    directives: Vec<Directive>,
+}
+
+struct Directive {
+    pattern: String,
+    enabled: bool,
+}
+
+impl Filter {
+    /// Filter configured by the environment at the moment of expansion.
+    pub(crate) fn current() -> Result<Rc<Filter>, String> {
+        thread_local! {
+            static CACHE: RefCell<Option<(String, Rc<Filter>)>> = const { RefCell::new(None) };
+        }
+
+        let spec = env::var(ENV_VAR).unwrap_or_default();
+
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            match *cache {
+                Some((ref cached_spec, ref filter)) if *cached_spec == spec => Ok(filter.clone()),
+                _ => {
+                    let filter = Rc::new(Filter::parse(&spec)?);
+                    *cache = Some((spec, filter.clone()));
+                    Ok(filter)
+                }
+            }
+        })
+    }
+
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut directives = Vec::new();
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let (pattern, enabled) = match directive.rfind('=') {
+                None => (directive, Some(true)),
+                Some(pos) => (
+                    directive[..pos].trim(),
+                    match directive[pos + 1..].trim() {
+                        "on" => Some(true),
+                        "off" => Some(false),
+                        _ => None,
+                    },
+                ),
+            };
+
+            match enabled {
+                Some(enabled) if !pattern.is_empty() => directives.push(Directive {
+                    pattern: pattern.to_string(),
+                    enabled,
+                }),
+                _ => {
+                    return Err(format!(
+                        "invalid directive `{}` in {}, expected `<pattern>[=on|off]`",
+                        directive, ENV_VAR
+                    ))
+                }
+            }
+        }
+
+        Ok(Filter { directives })
+    }
+
+    /// Decides whether a function with the qualified `name` must be instrumented.
+    pub(crate) fn enabled(&self, name: &str) -> bool {
+        let mut best: Option<&Directive> = None;
+
+        for directive in self
+            .directives
+            .iter()
+            .filter(|d| pattern::glob_path(&d.pattern, name))
+        {
+            match best {
+                Some(b) if b.pattern.len() > directive.pattern.len() => (),
+                _ => best = Some(directive),
+            }
+        }
+
+        best.is_none_or(|directive| directive.enabled)
+    }
+}
@@ -0,0 +1,2524 @@
+mod args;
+mod filter;
+mod module;
+mod pattern;
+
+use args::{ArgFormat, Builtin, Prefix, Receiver};
+use quote::{quote, quote_spanned, ToTokens};
+use std::env;
+use std::ops::Deref;
+use syn::parse::{Parse, Parser};
+use syn::parse_quote;
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+
+const MACRO_NAME: &str = "trace";
+const CRATE_NAME: &str = "trace";
+
+/// Traces the functions of the item it is attached to, i.e., a function, `impl`, `mod`, `trait`
+/// or `extern` block; the options are described in the README.
+///
+/// `#[trace] mod foo;` reads the module from its file and traces its items as if it was declared inline.
+/// The compiler accepts out-of-line modules as an input of an attribute macro on nightly only,
+/// with `#![feature(proc_macro_hygiene)]` in the crate root, see `examples/example_mod_file.rs`,
+/// which is built with `RUSTFLAGS='--cfg nightly'`.
+#[proc_macro_attribute]
+pub fn trace(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    if cfg!(feature = "off") {
+        return compile_out(input).into();
+    }
+
+    if let Err(error) = filter::Filter::current() {
+        return syn::Error::new(proc_macro2::Span::call_site(), error)
+            .to_compile_error()
+            .into();
+    }
+
+    let raw_args = match args::parse_raw_args.parse(args) {
+        Ok(raw_args) => raw_args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let attr = match args::Args::from_raw_args(raw_args) {
+        Ok(args) => AttrApplication::Directly(args),
+        Err(errors) => {
+            return errors
+                .iter()
+                .map(syn::Error::to_compile_error)
+                .collect::<proc_macro2::TokenStream>()
+                .into()
+        }
+    };
+
+    let output = if let Ok(ref mut item) = syn::Item::parse.parse(input.clone()) {
+        let on_fn = matches!(item, syn::Item::Fn(_));
+        // The path of the `trace` crate is looked up in the manifest once per invocation.
+        let krate = crate_path();
+        match transform_item(std::slice::from_ref(&attr), &krate, &[], item) {
+            Ok(()) => {
+                let warnings = unused_warnings(&attr, on_fn);
+                match item {
+                    syn::Item::Fn(ref mut item_fn) => {
+                        item_fn.block.stmts.splice(0..0, warnings);
+                        item_fn.into_token_stream()
+                    }
+                    _ => {
+                        let warnings = warnings_item(scoped_warnings(warnings, item_attrs(item)));
+                        quote! { #item #warnings }
+                    }
+                }
+            }
+            Err(errors) => errors
+                .iter()
+                .map(syn::Error::to_compile_error)
+                .collect::<proc_macro2::TokenStream>(),
+        }
+    } else {
+        let input2 = proc_macro2::TokenStream::from(input);
+        syn::Error::new_spanned(
+            input2,
+            "expected one of: `fn`, `impl`, `mod`, `trait`, `extern`",
+        )
+        .to_compile_error()
+    };
+
+    output.into()
+}
+
+/// Leaves the input untraced, with the `off` feature, yet with the same API,
+/// i.e., with the traced wrappers of the required methods of traits.
+/// The `#[trace]` attributes within the input are removed, as they may apply to statements
+/// or refer to a `trace` that is not in scope.
+fn compile_out(input: proc_macro::TokenStream) -> proc_macro2::TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+    let output = match syn::Item::parse.parse2(input.clone()) {
+        Ok(mut item) => match add_traced_wrappers(&[], &mut item) {
+            Ok(()) => item.into_token_stream(),
+            Err(errors) => return errors.iter().map(syn::Error::to_compile_error).collect(),
+        },
+        Err(_) => input,
+    };
+
+    strip_trace_attrs(output)
+}
+
+/// Adds the traced wrappers to the traits within `item`, as `transform_item` does;
+/// `scope` lists the modules enclosing the `item` within the item `#[trace]` is attached to.
+fn add_traced_wrappers(scope: &[String], item: &mut syn::Item) -> Result<(), Vec<syn::Error>> {
+    match *item {
+        syn::Item::Trait(ref mut item_trait) => {
+            let names = trait_item_names(&item_trait.items);
+            let wrappers = item_trait
+                .items
+                .iter()
+                .filter_map(|trait_item| match *trait_item {
+                    syn::TraitItem::Method(ref trait_item_method)
+                        if trait_item_method.default.is_none() =>
+                    {
+                        Some(traced_wrapper(trait_item_method, &names).map(syn::TraitItem::Method))
+                    }
+                    _ => None,
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| vec![error])?;
+            item_trait.items.extend(wrappers);
+        }
+        syn::Item::Mod(ref mut item_mod) => {
+            if item_mod.content.is_none() {
+                module::load(item_mod, scope)?;
+            }
+
+            let scope = scope
+                .iter()
+                .cloned()
+                .chain(Some(item_mod.ident.to_string()))
+                .collect::<Vec<_>>();
+            if let Some((_, ref mut items)) = item_mod.content {
+                for item in items {
+                    add_traced_wrappers(&scope, item)?;
+                }
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Removes the `#[trace]` attributes, with or without arguments, from the tokens.
+fn strip_trace_attrs(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    use proc_macro2::TokenTree;
+
+    let is_trace_attr = |tree: Option<&TokenTree>| match tree {
+        Some(TokenTree::Group(group)) if group.delimiter() == proc_macro2::Delimiter::Bracket => {
+            matches!(group.stream().into_iter().next(), Some(TokenTree::Ident(ident)) if ident == MACRO_NAME)
+        }
+        _ => false,
+    };
+
+    let mut trees = tokens.into_iter().peekable();
+    let mut stripped = Vec::new();
+    while let Some(tree) = trees.next() {
+        match tree {
+            TokenTree::Punct(ref punct)
+                if punct.as_char() == '#' && is_trace_attr(trees.peek()) =>
+            {
+                trees.next();
+            }
+            TokenTree::Group(group) => {
+                let mut stripped_group =
+                    proc_macro2::Group::new(group.delimiter(), strip_trace_attrs(group.stream()));
+                stripped_group.set_span(group.span());
+                stripped.push(TokenTree::Group(stripped_group));
+            }
+            tree => stripped.push(tree),
+        }
+    }
+
+    stripped.into_iter().collect()
+}
+
+/// Transforms an `item` with the given `attrs` applied;
+/// `scope` lists the modules enclosing the `item` within the item `#[trace]` is attached to.
+fn transform_item(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    item: &mut syn::Item,
+) -> Result<(), Vec<syn::Error>> {
+    match item {
+        syn::Item::Fn(ref mut item_fn) => transform_fn(attrs, krate, scope, item_fn),
+        syn::Item::Mod(ref mut item_mod) => transform_mod(attrs, krate, scope, item_mod),
+        syn::Item::Impl(ref mut item_impl) => transform_impl(attrs, krate, scope, item_impl),
+        syn::Item::Trait(ref mut item_trait) => transform_trait(attrs, krate, scope, item_trait),
+        syn::Item::ForeignMod(_) => transform_foreign_mod(attrs, krate, scope, item),
+        _ => Err(vec![syn::Error::new_spanned(
+            item,
+            "#[trace] is not supported for this item",
+        )]),
+    }
+}
+
+fn transform_fn(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    item_fn: &mut syn::ItemFn,
+) -> Result<(), Vec<syn::Error>> {
+    let local_name = item_fn.ident.to_string();
+    transform_named_fn(attrs, krate, scope, &local_name, item_fn)
+}
+
+/// Transforms a function named `local_name`, which is qualified by the enclosing functions, if any.
+fn transform_named_fn(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    local_name: &str,
+    item_fn: &mut syn::ItemFn,
+) -> Result<(), Vec<syn::Error>> {
+    let is_pub = !matches!(item_fn.vis, syn::Visibility::Inherited);
+    if is_filtered_out(attrs, is_pub, is_trivial(&item_fn.block)) {
+        return Ok(());
+    }
+
+    transform_body(
+        attrs,
+        krate,
+        scope,
+        local_name,
+        &item_fn.ident,
+        &mut item_fn.block,
+    )?;
+
+    // The traced block is constructed even if it is compiled out to report the same errors.
+    let mut decl = (*item_fn.decl).clone();
+    let traced_block = construct_traced_block(
+        attrs,
+        krate,
+        BodyKind::of(&item_fn.asyncness),
+        local_name,
+        local_name,
+        &item_fn.ident,
+        &mut decl,
+        &item_fn.block,
+    )?;
+
+    if is_compiled_in(scope, &item_fn.ident.to_string())? {
+        *item_fn.decl = decl;
+        *item_fn.block = traced_block;
+    } else {
+        item_fn.block.stmts.insert(0, track_compile_filter());
+    }
+
+    Ok(())
+}
+
+fn transform_mod(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    item_mod: &mut syn::ItemMod,
+) -> Result<(), Vec<syn::Error>> {
+    if item_mod.content.is_none() {
+        module::load(item_mod, scope)?;
+    }
+
+    let scope = scope
+        .iter()
+        .cloned()
+        .chain(Some(item_mod.ident.to_string()))
+        .collect::<Vec<_>>();
+
+    if let Some((_, items)) = item_mod.content.as_mut() {
+        let processable = items.iter_mut().filter(|item| {
+            matches!(
+                item,
+                syn::Item::Fn(_)
+                    | syn::Item::Mod(_)
+                    | syn::Item::Impl(_)
+                    | syn::Item::Trait(_)
+                    | syn::Item::ForeignMod(_)
+            )
+        });
+
+        let mut warnings = Vec::new();
+        for item in processable {
+            let raw_local_attr: &mut Vec<syn::Attribute> = match item {
+                syn::Item::Fn(ref mut item_fn) => Ok(item_fn.attrs.as_mut()),
+                syn::Item::Mod(ref mut item_mod) => Ok(item_mod.attrs.as_mut()),
+                syn::Item::Impl(ref mut item_impl) => Ok(item_impl.attrs.as_mut()),
+                syn::Item::Trait(ref mut item_trait) => Ok(item_trait.attrs.as_mut()),
+                syn::Item::ForeignMod(ref mut item_foreign_mod) => {
+                    Ok(item_foreign_mod.attrs.as_mut())
+                }
+                _ => Err(vec![syn::Error::new_spanned(
+                    "UNREACHABLE".into_token_stream(),
+                    "UNREACHABLE",
+                )]),
+            }?;
+
+            let local_attrs = extract_local_attrs(raw_local_attr)?;
+            let attrs = create_context(attrs, item_segment(item), local_attrs.clone());
+            transform_item(&attrs, krate, &scope, item)?;
+
+            if let Some(local_attrs) = local_attrs {
+                warnings.extend(scoped_warnings(
+                    unused_warnings(&local_attrs, matches!(item, syn::Item::Fn(_))),
+                    item_attrs(item),
+                ));
+            }
+        }
+        items.extend(warnings_item(warnings));
+    }
+
+    Ok(())
+}
+
+fn transform_impl(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    item_impl: &mut syn::ItemImpl,
+) -> Result<(), Vec<syn::Error>> {
+    let self_name = type_name(&item_impl.self_ty);
+    let scope = scope
+        .iter()
+        .cloned()
+        .chain(Some(self_name.clone()))
+        .collect::<Vec<_>>();
+
+    for impl_item in item_impl.items.iter_mut() {
+        if let syn::ImplItem::Method(ref mut impl_item_method) = impl_item {
+            let local_attrs = extract_local_attrs(impl_item_method.attrs.as_mut())?;
+            let attrs = create_context(
+                attrs,
+                Some(pattern::Segment::Item(
+                    impl_item_method.sig.ident.to_string(),
+                )),
+                local_attrs.clone(),
+            );
+
+            // Methods of trait implementations are as visible as the trait.
+            let is_pub = item_impl.trait_.is_some()
+                || !matches!(impl_item_method.vis, syn::Visibility::Inherited);
+            if !is_filtered_out(&attrs, is_pub, is_trivial(&impl_item_method.block)) {
+                let local_name = format!("{}::{}", self_name, impl_item_method.sig.ident);
+                transform_body(
+                    &attrs,
+                    krate,
+                    &scope,
+                    &local_name,
+                    &impl_item_method.sig.ident,
+                    &mut impl_item_method.block,
+                )?;
+
+                let mut decl = impl_item_method.sig.decl.clone();
+                let traced_block = construct_traced_block(
+                    &attrs,
+                    krate,
+                    BodyKind::of(&impl_item_method.sig.asyncness),
+                    &local_name,
+                    &impl_item_method.sig.ident.to_string(),
+                    &impl_item_method.sig.ident,
+                    &mut decl,
+                    &impl_item_method.block,
+                )?;
+
+                if is_compiled_in(&scope, &impl_item_method.sig.ident.to_string())? {
+                    impl_item_method.sig.decl = decl;
+                    impl_item_method.block = traced_block;
+                } else {
+                    impl_item_method
+                        .block
+                        .stmts
+                        .insert(0, track_compile_filter());
+                }
+            }
+
+            if let Some(local_attrs) = local_attrs {
+                let warnings = unused_warnings(&local_attrs, true);
+                impl_item_method.block.stmts.splice(0..0, warnings);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn transform_trait(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    item_trait: &mut syn::ItemTrait,
+) -> Result<(), Vec<syn::Error>> {
+    let trait_name = item_trait.ident.to_string();
+    let scope = scope
+        .iter()
+        .cloned()
+        .chain(Some(trait_name.clone()))
+        .collect::<Vec<_>>();
+
+    let names = trait_item_names(&item_trait.items);
+    let mut wrappers = Vec::new();
+
+    for trait_item in item_trait.items.iter_mut() {
+        if let syn::TraitItem::Method(ref mut trait_item_method) = trait_item {
+            let local_attrs = extract_local_attrs(trait_item_method.attrs.as_mut())?;
+            let attrs = create_context(
+                attrs,
+                Some(pattern::Segment::Item(
+                    trait_item_method.sig.ident.to_string(),
+                )),
+                local_attrs.clone(),
+            );
+            let name = format!("{}::{}", trait_name, trait_item_method.sig.ident);
+
+            // The unused arguments are known once the method is traced.
+            let add_warnings = |method: &mut syn::TraitItemMethod| {
+                if let (Some(local_attrs), Some(block)) = (&local_attrs, method.default.as_mut()) {
+                    block.stmts.splice(0..0, unused_warnings(local_attrs, true));
+                }
+            };
+
+            // Implementations are out of reach, a required method gets a traced wrapper instead.
+            if trait_item_method.default.is_some() {
+                transform_trait_method(&attrs, krate, &scope, &name, trait_item_method)?;
+                add_warnings(trait_item_method);
+            } else {
+                let mut wrapper =
+                    traced_wrapper(trait_item_method, &names).map_err(|error| vec![error])?;
+                transform_trait_method(&attrs, krate, &scope, &name, &mut wrapper)?;
+                add_warnings(&mut wrapper);
+                wrappers.push(syn::TraitItem::Method(wrapper));
+            }
+        }
+    }
+
+    item_trait.items.extend(wrappers);
+
+    Ok(())
+}
+
+/// Traces a method of a trait with a default body; `name` is qualified by the trait.
+fn transform_trait_method(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    name: &str,
+    trait_item_method: &mut syn::TraitItemMethod,
+) -> Result<(), Vec<syn::Error>> {
+    let block = match trait_item_method.default {
+        Some(ref mut block) => block,
+        None => return Ok(()),
+    };
+
+    // Methods of a trait are as visible as the trait.
+    if is_filtered_out(attrs, true, is_trivial(block)) {
+        return Ok(());
+    }
+
+    transform_body(
+        attrs,
+        krate,
+        scope,
+        name,
+        &trait_item_method.sig.ident,
+        block,
+    )?;
+
+    let mut decl = trait_item_method.sig.decl.clone();
+    let traced_block = construct_traced_block(
+        attrs,
+        krate,
+        BodyKind::of(&trait_item_method.sig.asyncness),
+        name,
+        name,
+        &trait_item_method.sig.ident,
+        &mut decl,
+        block,
+    )?;
+
+    if is_compiled_in(scope, &trait_item_method.sig.ident.to_string())? {
+        trait_item_method.sig.decl = decl;
+        *block = traced_block;
+    } else {
+        block.stmts.insert(0, track_compile_filter());
+    }
+
+    Ok(())
+}
+
+/// The names of the methods and the constants of a trait, which a traced wrapper must not take.
+fn trait_item_names(items: &[syn::TraitItem]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|trait_item| match *trait_item {
+            syn::TraitItem::Method(ref trait_item_method) => {
+                Some(trait_item_method.sig.ident.to_string())
+            }
+            syn::TraitItem::Const(ref trait_item_const) => Some(trait_item_const.ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generates a provided method `<name>_traced` calling the required method `<name>` of a trait,
+/// e.g., `fn area_traced(&self) -> f64 { Self::area(self) }`; `names` are the items of the trait.
+fn traced_wrapper(
+    trait_item_method: &syn::TraitItemMethod,
+    names: &[String],
+) -> syn::Result<syn::TraitItemMethod> {
+    let ident = &trait_item_method.sig.ident;
+    let wrapper_name = format!("{}_traced", ident);
+    if names.contains(&wrapper_name) {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!(
+                "the traced wrapper `{}` of `{}` clashes with an item of the trait",
+                wrapper_name, ident
+            ),
+        ));
+    }
+
+    let mut sig = trait_item_method.sig.clone();
+    sig.ident = proc_macro2::Ident::new(&wrapper_name, ident.span());
+
+    // Unlike the required method, the provided wrapper moves `Self` by value only if it is sized.
+    let moves_self = sig.decl.inputs.iter().any(|input| match *input {
+        syn::FnArg::SelfValue(_) => true,
+        syn::FnArg::Captured(syn::ArgCaptured { ref ty, .. }) | syn::FnArg::Ignored(ref ty) => {
+            has_self_by_value(ty)
+        }
+        _ => false,
+    }) || match sig.decl.output {
+        syn::ReturnType::Type(_, ref ty) => has_self_by_value(ty),
+        syn::ReturnType::Default => false,
+    };
+    if moves_self {
+        sig.decl
+            .generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote! { Self: Sized });
+    }
+
+    let call_args = forward_args(&mut sig.decl);
+
+    let mut call = quote! { Self::#ident(#(#call_args),*) };
+    if sig.asyncness.is_some() {
+        call = quote! { #call.await };
+    }
+    if sig.unsafety.is_some() {
+        call = quote! { unsafe { #call } };
+    }
+
+    let doc = format!(" Traced call of [`{}`](Self::{}).", ident, ident);
+
+    Ok(parse_quote! {
+        #[doc = #doc]
+        #sig {
+            #call
+        }
+    })
+}
+
+/// Checks whether `ty` holds a `Self`, other than behind a reference or a pointer, e.g., `Self` or `Option<Self>`.
+fn has_self_by_value(ty: &syn::Type) -> bool {
+    match *ty {
+        syn::Type::Path(syn::TypePath {
+            qself: None,
+            ref path,
+        }) => {
+            path.is_ident("Self")
+                || path.segments.iter().any(|segment| match segment.arguments {
+                    syn::PathArguments::AngleBracketed(ref arguments) => {
+                        arguments.args.iter().any(|argument| match *argument {
+                            syn::GenericArgument::Type(ref ty) => has_self_by_value(ty),
+                            _ => false,
+                        })
+                    }
+                    _ => false,
+                })
+        }
+        syn::Type::Tuple(ref type_tuple) => type_tuple.elems.iter().any(has_self_by_value),
+        syn::Type::Array(syn::TypeArray { ref elem, .. })
+        | syn::Type::Slice(syn::TypeSlice { ref elem, .. })
+        | syn::Type::Paren(syn::TypeParen { ref elem, .. })
+        | syn::Type::Group(syn::TypeGroup { ref elem, .. }) => has_self_by_value(elem),
+        _ => false,
+    }
+}
+
+/// Names the parameters of a function declaration for a wrapper to forward them,
+/// returns the arguments of the call of the wrapped function.
+/// Unnamed parameters, e.g., in `fn f(&self, u8);` or `fn f(&self, _: u8);`, are named after their position
+/// as `extract_args` names them, so that they are traced as `_` either way.
+fn forward_args(fn_decl: &mut syn::FnDecl) -> Vec<proc_macro2::TokenStream> {
+    let mut call_args = Vec::new();
+
+    for (index, input) in fn_decl.inputs.iter_mut().enumerate() {
+        let name = match *input {
+            syn::FnArg::SelfRef(_) | syn::FnArg::SelfValue(_) => {
+                call_args.push(quote! { self });
+                continue;
+            }
+            syn::FnArg::Captured(syn::ArgCaptured {
+                pat: syn::Pat::Ident(ref pat_ident),
+                ..
+            }) if pat_ident.subpat.is_none() => pat_ident.ident.clone(),
+            _ => unnamed_arg(index),
+        };
+
+        *input = match *input {
+            syn::FnArg::Captured(ref arg_captured) => {
+                let ty = &arg_captured.ty;
+                parse_quote! { #name: #ty }
+            }
+            syn::FnArg::Ignored(ref ty) => parse_quote! { #name: #ty },
+            _ => parse_quote! { #name },
+        };
+        call_args.push(quote! { #name });
+    }
+
+    call_args
+}
+
+/// Replaces an `extern` block with the one where each foreign function `foo` is imported
+/// as `__trace_ffi_foo`, followed by the traced wrappers `unsafe fn foo` calling the imports.
+fn transform_foreign_mod(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    item: &mut syn::Item,
+) -> Result<(), Vec<syn::Error>> {
+    let mut item_foreign_mod = match *item {
+        syn::Item::ForeignMod(ref item_foreign_mod) => item_foreign_mod.clone(),
+        _ => return Ok(()),
+    };
+
+    let mut wrappers = Vec::new();
+    let mut warnings = Vec::new();
+    let mut is_tracking = false;
+
+    for foreign_item in item_foreign_mod.items.iter_mut() {
+        let foreign_item_fn = match *foreign_item {
+            syn::ForeignItem::Fn(ref mut foreign_item_fn) => foreign_item_fn,
+            _ => continue,
+        };
+
+        let local_attrs = extract_local_attrs(foreign_item_fn.attrs.as_mut())?;
+        let attrs = create_context(
+            attrs,
+            Some(pattern::Segment::Item(foreign_item_fn.ident.to_string())),
+            local_attrs.clone(),
+        );
+
+        // Variadic functions cannot be forwarded to.
+        let is_pub = !matches!(foreign_item_fn.vis, syn::Visibility::Inherited);
+        if foreign_item_fn.decl.variadic.is_none() && !is_filtered_out(&attrs, is_pub, false) {
+            let ident = foreign_item_fn.ident.clone();
+            let ffi_ident =
+                proc_macro2::Ident::new(&format!("__trace_ffi_{}", ident), ident.span());
+
+            let mut decl = (*foreign_item_fn.decl).clone();
+            let call_args = forward_args(&mut decl);
+            let generics = &decl.generics;
+            let where_clause = &decl.generics.where_clause;
+            let inputs = &decl.inputs;
+            let output = &decl.output;
+            let vis = &foreign_item_fn.vis;
+            let wrapper_attrs = foreign_item_fn
+                .attrs
+                .iter()
+                .filter(|attr| !attr.path.is_ident("link_name"));
+
+            let mut wrapper: syn::ItemFn = parse_quote! {
+                #(#wrapper_attrs)*
+                #vis unsafe fn #ident #generics(#inputs) #output #where_clause {
+                    unsafe { #ffi_ident(#(#call_args),*) }
+                }
+            };
+
+            let traced_block = construct_traced_block(
+                &attrs,
+                krate,
+                BodyKind::Fn,
+                &ident.to_string(),
+                &ident.to_string(),
+                &ident,
+                &mut wrapper.decl,
+                &wrapper.block,
+            )?;
+
+            if is_compiled_in(scope, &ident.to_string())? {
+                *wrapper.block = traced_block;
+
+                // The import keeps its symbol under the new name.
+                if !foreign_item_fn
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path.is_ident("link_name"))
+                {
+                    let link_name = ident.to_string();
+                    foreign_item_fn
+                        .attrs
+                        .push(parse_quote! { #[link_name = #link_name] });
+                }
+                foreign_item_fn.ident = ffi_ident;
+                foreign_item_fn.vis = syn::Visibility::Inherited;
+                wrappers.push(wrapper);
+            } else {
+                is_tracking = true;
+            }
+        }
+
+        if let Some(local_attrs) = local_attrs {
+            warnings.extend(scoped_warnings(
+                unused_warnings(&local_attrs, true),
+                &foreign_item_fn.attrs,
+            ));
+        }
+    }
+    let warnings = warnings_item(warnings);
+
+    let track_compile_filter = if is_tracking {
+        let stmt = track_compile_filter();
+        quote! { #stmt }
+    } else {
+        quote!()
+    };
+
+    *item = syn::Item::Verbatim(syn::ItemVerbatim {
+        tts: quote! {
+            #item_foreign_mod
+            #(#wrappers)*
+            #track_compile_filter
+            #warnings
+        },
+    });
+
+    Ok(())
+}
+
+/// Traces the functions and the closures defined within the body of a function named `local_name`,
+/// as requested by `nested` and `closures`; `scope` lists the items enclosing the function.
+fn transform_body(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    scope: &[String],
+    local_name: &str,
+    ident: &proc_macro2::Ident,
+    block: &mut syn::Block,
+) -> Result<(), Vec<syn::Error>> {
+    let nested = attrs.iter().any(|attr| attr.nested);
+    let closures = attrs.iter().any(|attr| attr.closures);
+    if !nested && !closures {
+        return Ok(());
+    }
+
+    // Options selecting the arguments of the function do not apply to the items within.
+    let attrs = attrs
+        .iter()
+        .cloned()
+        .map(|attr| match attr {
+            AttrApplication::Directly(args) => {
+                AttrApplication::Directly(args.without_item_filters())
+            }
+            attr => attr,
+        })
+        .collect::<Vec<_>>();
+    let scope = scope
+        .iter()
+        .cloned()
+        .chain(Some(ident.to_string()))
+        .collect::<Vec<_>>();
+
+    let mut visitor = BodyVisitor {
+        attrs: &attrs,
+        krate,
+        scope: &scope,
+        local_name,
+        nested,
+        closures,
+        closure_names: Vec::new(),
+        errors: Vec::new(),
+    };
+    visitor.visit_block_mut(block);
+
+    if visitor.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(visitor.errors)
+    }
+}
+
+/// Finds the functions and the closures bound by `let` within a body of a function.
+struct BodyVisitor<'a> {
+    attrs: &'a [AttrApplication],
+    krate: &'a proc_macro2::TokenStream,
+    scope: &'a [String],
+    local_name: &'a str,
+    nested: bool,
+    closures: bool,
+    /// The names given to the closures so far.
+    closure_names: Vec<String>,
+    errors: Vec<syn::Error>,
+}
+
+impl<'a> BodyVisitor<'a> {
+    fn transform_fn(&self, item_fn: &mut syn::ItemFn) -> Result<(), Vec<syn::Error>> {
+        let local_attrs = extract_local_attrs(item_fn.attrs.as_mut())?;
+        let attrs = create_context(
+            self.attrs,
+            Some(pattern::Segment::Item(item_fn.ident.to_string())),
+            local_attrs.clone(),
+        );
+        let local_name = format!("{}::{}", self.local_name, item_fn.ident);
+
+        // The functions within are handled by the nested invocation.
+        transform_named_fn(&attrs, self.krate, self.scope, &local_name, item_fn)?;
+
+        if let Some(local_attrs) = local_attrs {
+            let warnings = unused_warnings(&local_attrs, true);
+            item_fn.block.stmts.splice(0..0, warnings);
+        }
+
+        Ok(())
+    }
+
+    /// Names a closure by its line, `{closure@<line>}`, or, if the line does not tell it apart,
+    /// e.g., for a closure expanded from a macro or read from a module file, by its index within the function,
+    /// `{closure#<index>}`.
+    fn closure_name(&mut self, closure: &syn::ExprClosure) -> String {
+        let by_line = Some(closure.span())
+            .filter(|_| proc_macro::is_available())
+            .map(proc_macro2::Span::unwrap)
+            .filter(|span| {
+                let call_site = proc_macro::Span::call_site();
+                span.file() == call_site.file()
+                    && (span.line(), span.column()) != (call_site.line(), call_site.column())
+            })
+            .map(|span| format!("{{closure@{}}}", span.line()));
+
+        let name = match by_line {
+            Some(name) if !self.closure_names.contains(&name) => name,
+            _ => format!("{{closure#{}}}", self.closure_names.len()),
+        };
+        self.closure_names.push(name.clone());
+
+        name
+    }
+
+    fn transform_closure(
+        &mut self,
+        local_attrs: &mut Vec<syn::Attribute>,
+        closure: &mut syn::ExprClosure,
+    ) -> Result<(), Vec<syn::Error>> {
+        let local_attrs = extract_local_attrs(local_attrs)?;
+        let name = self.closure_name(closure);
+        let attrs = create_context(
+            self.attrs,
+            Some(pattern::Segment::Item(name.clone())),
+            local_attrs.clone(),
+        );
+
+        let block: syn::Block = match *closure.body {
+            syn::Expr::Block(ref expr_block) => expr_block.block.clone(),
+            ref body => parse_quote! {{ #body }},
+        };
+
+        if !is_filtered_out(&attrs, false, is_trivial(&block)) {
+            let local_name = format!("{}::{}", self.local_name, name);
+            let ident = proc_macro2::Ident::new("closure", closure.span());
+            let mut decl = syn::FnDecl {
+                fn_token: Default::default(),
+                generics: Default::default(),
+                paren_token: Default::default(),
+                inputs: closure.inputs.clone(),
+                variadic: None,
+                output: closure.output.clone(),
+            };
+
+            let traced_block = construct_traced_block(
+                &attrs,
+                self.krate,
+                BodyKind::Closure,
+                &local_name,
+                &local_name,
+                &ident,
+                &mut decl,
+                &block,
+            )?;
+
+            if is_compiled_in(self.scope, &name)? {
+                closure.inputs = decl.inputs;
+                *closure.body = parse_quote! { #traced_block };
+            }
+        }
+
+        if let Some(local_attrs) = local_attrs {
+            let warnings = unused_warnings(&local_attrs, true);
+            if !warnings.is_empty() {
+                let body = &closure.body;
+                *closure.body = parse_quote! {{ #(#warnings)* #body }};
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> VisitMut for BodyVisitor<'a> {
+    fn visit_item_mut(&mut self, item: &mut syn::Item) {
+        // Other items, e.g., `impl` blocks, are not traced, nor anything within them.
+        if let syn::Item::Fn(ref mut item_fn) = *item {
+            if self.nested {
+                if let Err(errors) = self.transform_fn(item_fn) {
+                    self.errors.extend(errors);
+                }
+            }
+        }
+    }
+
+    fn visit_local_mut(&mut self, local: &mut syn::Local) {
+        // Closures within closures first.
+        visit_mut::visit_local_mut(self, local);
+
+        if !self.closures {
+            return;
+        }
+
+        if let Some((_, ref mut init)) = local.init {
+            if let syn::Expr::Closure(ref mut closure) = **init {
+                // Async closures are not supported.
+                if closure.asyncness.is_none() {
+                    if let Err(errors) = self.transform_closure(&mut local.attrs, closure) {
+                        self.errors.extend(errors);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How the original body of a function is run by the traced one.
+#[derive(Clone, Copy)]
+enum BodyKind {
+    Fn,
+    AsyncFn,
+    /// The body of a closure borrows the captures of the closure rather than moving them.
+    Closure,
+}
+
+impl BodyKind {
+    fn of(asyncness: &Option<syn::token::Async>) -> Self {
+        if asyncness.is_some() {
+            BodyKind::AsyncFn
+        } else {
+            BodyKind::Fn
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn construct_traced_block(
+    attrs: &[AttrApplication],
+    krate: &proc_macro2::TokenStream,
+    body_kind: BodyKind,
+    // Name of the function qualified by its `impl` type, if any, but not by its module.
+    local_name: &str,
+    // Name of the function in the output, e.g., qualified by its trait.
+    printed_name: &str,
+    ident: &proc_macro2::Ident,
+    // Parameters binding nothing are renamed to be traced as a whole.
+    fn_decl: &mut syn::FnDecl,
+    original_block: &syn::Block,
+) -> Result<syn::Block, Vec<syn::Error>> {
+    let returned_local_name = format!("{}::{{returned}}", local_name);
+    let returned_printed_name = format!("{}::{{returned}}", printed_name);
+    let printed_name = &escape_format(printed_name);
+    let mut bind_stmts = Vec::new();
+    let fn_arguments = extract_args(attrs, fn_decl, &mut bind_stmts);
+
+    // A returned future is traced until it completes.
+    let future = match body_kind {
+        BodyKind::Fn => returned(&fn_decl.output, "Future", &["BoxFuture", "LocalBoxFuture"]),
+        BodyKind::AsyncFn | BodyKind::Closure => None,
+    };
+
+    // The items of a returned iterator or stream are traced with the innermost `items`.
+    let items = attrs
+        .iter()
+        .rev()
+        .find_map(|attr| attr.items.map(|(_, limit)| (attr, limit)))
+        .filter(|_| future.is_none())
+        .and_then(|(attr, limit)| {
+            // The subtraits name an iterator as well, e.g., `impl DoubleEndedIterator<Item = u8>`.
+            let iter = ["Iterator", "DoubleEndedIterator", "ExactSizeIterator"]
+                .iter()
+                .find_map(|name| match returned(&fn_decl.output, name, &[]) {
+                    Some(returned @ Returned::Impl) | Some(returned @ Returned::Boxed) => {
+                        Some((quote!(TracedIter), returned))
+                    }
+                    _ => None,
+                });
+            let stream =
+                || match returned(&fn_decl.output, "Stream", &["BoxStream", "LocalBoxStream"]) {
+                    Some(returned @ Returned::Impl) | Some(returned @ Returned::Pinned) => {
+                        Some((quote!(TracedStream), returned))
+                    }
+                    _ => None,
+                };
+
+            let (wrapper, returned) = iter.or_else(stream)?;
+            attr.note_iterator();
+            Some((wrapper, returned, limit))
+        });
+
+    // A returned closure is traced as a function of its own.
+    let closure = attrs
+        .iter()
+        .rev()
+        .find(|attr| attr.returned_closure.is_some())
+        .filter(|_| future.is_none())
+        .and_then(|attr| {
+            let closure = returned_closure(&fn_decl.output)?;
+            attr.note_returned_closure();
+            Some(closure)
+        });
+
+    // With `mut_after`, the `&mut` arguments, and `&mut self`, are logged again at exit.
+    let mut_after = attrs
+        .iter()
+        .rev()
+        .find_map(|attr| attr.mut_after.map(|(_, mut_after)| (attr, mut_after)))
+        .filter(|_| future.is_none())
+        .and_then(|(attr, mut_after)| {
+            let mut_refs = extract_mut_refs(fn_decl, &fn_arguments, mut_after.receiver);
+            if mut_refs.is_empty() {
+                None
+            } else {
+                attr.note_mut_after();
+                Some((mut_after, mut_refs))
+            }
+        });
+
+    // With `receiver`, the object a method runs on is logged first.
+    let receiver = match fn_decl.inputs.first().map(|input| input.into_value()) {
+        Some(syn::FnArg::SelfRef(_)) => Some(quote! { self }),
+        Some(syn::FnArg::SelfValue(_)) => Some(quote! { &self }),
+        _ => None,
+    }
+    .and_then(|receiver_ref| {
+        let receiver = attrs.iter().rev().find_map(|attr| {
+            attr.receiver.as_ref().map(|(_, receiver)| {
+                attr.note_receiver();
+                receiver.clone()
+            })
+        });
+        // A format for `self` logs the object even without `receiver`.
+        let receiver = receiver.or_else(|| {
+            attrs
+                .iter()
+                .any(|attr| attr.args_format.keys().any(|ident| ident == "self"))
+                .then(|| Receiver {
+                    debug: true,
+                    ..Receiver::default()
+                })
+        })?;
+        Some((receiver_ref, receiver))
+    });
+
+    let names = fn_arguments
+        .iter()
+        .map(|inclusion| match inclusion {
+            Inclusion::Value(arg) | Inclusion::Skip(arg) => arg.name.clone(),
+        })
+        .chain(Some("res".to_string()))
+        .chain(
+            receiver
+                .as_ref()
+                .filter(|(_, receiver)| receiver.debug)
+                .map(|_| "self".to_string()),
+        )
+        .chain(items.as_ref().map(|_| "item".to_string()))
+        .chain(
+            mut_after
+                .iter()
+                .flat_map(|(_, mut_refs)| mut_refs.iter().map(|mut_ref| mut_ref.name.clone())),
+        )
+        .collect::<Vec<_>>();
+    attrs
+        .iter()
+        .for_each(|attr| attr.note_names(names.iter().cloned()));
+
+    let pretty = if attrs.iter().any(|attr| attr.pretty) {
+        "#"
+    } else {
+        ""
+    };
+
+    // Formats are inherited, the innermost one for a name wins.
+    let requested_format = |name: &str| {
+        attrs.iter().rev().find_map(|attr| {
+            attr.args_format
+                .iter()
+                .find(|(arg_ident, _)| *arg_ident == name)
+                .map(|(_, fmt)| fmt)
+        })
+    };
+
+    // The innermost `default` applies to the values without a format of their own.
+    let default_format = attrs.iter().rev().find_map(|attr| attr.default_format);
+
+    // The rule of `fmt(...)` for a value of type `ty`, along with the value behind the references
+    // the rule looks through, e.g., `*a` for `a: &Uuid`.
+    let type_format = |ty: Option<&syn::Type>, value: proc_macro2::TokenStream| {
+        let (ty, derefs) = referent_type(ty?);
+        let key = args::type_key(ty);
+        let format = attrs.iter().rev().find_map(|attr| attr.type_format(&key))?;
+        let value = (0..derefs).fold(value, |value, _| quote! { (*#value) });
+        Some((format, value))
+    };
+
+    // The format string and the logged expression of a value with the given format.
+    let logged_value = |format: Option<&ArgFormat>, value: proc_macro2::TokenStream| match format {
+        None => match default_format {
+            Some(builtin) => (
+                format!("{{:{}?}}", pretty),
+                builtin_value(krate, builtin, value),
+            ),
+            None => (format!("{{:{}?}}", pretty), debug_value(krate, value)),
+        },
+        Some(ArgFormat::Builtin(builtin)) => (
+            format!("{{:{}?}}", pretty),
+            builtin_value(krate, *builtin, value),
+        ),
+        Some(ArgFormat::Format(fmt)) => (fmt.clone(), value),
+        Some(ArgFormat::With(path)) => (
+            "{:?}".to_string(),
+            quote! { #krate::runtime::With(&#value, #path) },
+        ),
+        Some(ArgFormat::Expr(expr)) => (
+            format!("{{:{}?}}", pretty),
+            debug_value(krate, quote! { #expr }),
+        ),
+    };
+
+    // The format string and the logged expression of a value named `name` of type `ty`,
+    // by the format requested for its name, for its type, or else by `default`.
+    let log_value = |name: &str, ty: Option<&syn::Type>, value: proc_macro2::TokenStream| {
+        let (format, value) = match requested_format(name) {
+            Some(format) => (Some(format), value),
+            None => match type_format(ty, value.clone()) {
+                Some((format, value)) => (Some(format), value),
+                // A value behind references is logged as the referent, so that the fallback
+                // gives its type and size, e.g., `<Connection: 32 bytes>` for `&Connection`,
+                // unless the pointer itself is logged by `default(addr)`.
+                None => match ty {
+                    Some(ty) if !matches!(default_format, Some(Builtin::Addr)) => {
+                        let (_, derefs) = referent_type(ty);
+                        let value = (0..derefs).fold(value, |value, _| quote! { (*#value) });
+                        (None, value)
+                    }
+                    _ => (None, value),
+                },
+            },
+        };
+        logged_value(format, value)
+    };
+
+    // An expression logged in place of a value refers to the value by its name.
+    let binds_name = |name: &str| matches!(requested_format(name), Some(ArgFormat::Expr(_)));
+
+    // Arguments that look sensitive and are logged in full, i.e., neither disabled, redacted
+    // nor given a formatting, are reported as warnings; the innermost `sensitive(...)` names them.
+    let sensitive = attrs
+        .iter()
+        .rev()
+        .find_map(|attr| attr.sensitive.as_deref());
+    let sensitive_warnings = fn_arguments
+        .iter()
+        .filter_map(|inclusion| match inclusion {
+            Inclusion::Value(arg) if is_sensitive(&arg.name, sensitive) => Some(arg),
+            _ => None,
+        })
+        .filter(|arg| {
+            requested_format(&arg.name).is_none()
+                && type_format(arg.ty.as_ref(), quote!()).is_none()
+                && !matches!(default_format, Some(Builtin::Redact { .. }))
+        })
+        .map(|arg| {
+            let note = format!(
+                "`{}` is logged in full by `#[trace]`, \
+                 add it to `redact(...)` or give it a formatting",
+                arg.name
+            );
+            warning("__trace_sensitive_argument__", arg.value.span(), &note)
+        })
+        .collect::<Vec<_>>();
+
+    // Combine all enter prefixes.
+    let prefix_enter = attrs
+        .iter()
+        .map(|attr| &attr.prefix_enter)
+        .collect::<Prefix>()
+        .enter();
+
+    // Combine all exit prefixes.
+    let prefix_exit = attrs
+        .iter()
+        .map(|attr| &attr.prefix_exit)
+        .collect::<Prefix>()
+        .exit();
+
+    let mut receiver_formats = Vec::new();
+    let mut receiver_values = Vec::new();
+    if let Some((ref receiver_ref, ref receiver)) = receiver {
+        if receiver.debug {
+            let (format, value) = log_value("self", None, quote! { self });
+            receiver_formats.push(format!("self: {}", format));
+            receiver_values.push(value);
+        }
+        for (source, expr) in &receiver.projections {
+            receiver_formats.push(format!("{}: {{:{}?}}", escape_format(source), pretty));
+            receiver_values.push(debug_value(krate, quote! { #expr }));
+        }
+        if receiver.addr {
+            receiver_formats.push("self (address): {:p}".to_string());
+            receiver_values.push(quote! { #receiver_ref });
+        }
+        if receiver.type_ {
+            receiver_formats.push("self (type): {}".to_string());
+            receiver_values.push(quote! { #krate::runtime::type_name_of_val(#receiver_ref) });
+        }
+    }
+
+    // With `types`, the generic parameters of the function and the types of the values are logged.
+    let types = attrs.iter().any(|attr| attr.types);
+    let type_params = if types {
+        fn_decl
+            .generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    let typed_name = if type_params.is_empty() {
+        printed_name.to_string()
+    } else {
+        format!(
+            "{}::<{}>",
+            printed_name,
+            vec!["{}"; type_params.len()].join(", ")
+        )
+    };
+    let type_names = type_params
+        .iter()
+        .map(|param| quote! { #krate::runtime::type_name::<#param>() })
+        .collect::<Vec<_>>();
+    let type_of = |value: &proc_macro2::TokenStream| {
+        quote! { #krate::runtime::type_name_of_val(&#value) }
+    };
+
+    let entering_format = {
+        let sep = if fn_arguments.is_empty() && receiver_formats.is_empty() {
+            ""
+        } else {
+            "\n\t"
+        };
+
+        let arguments_format = receiver_formats
+            .into_iter()
+            .chain(fn_arguments.iter().map(|inclusion| {
+                match inclusion {
+                    Inclusion::Value(arg) => {
+                        // Argument's name and value must be printed,
+                        // find matching format if it was specified.
+                        let type_format = if types { "{} = " } else { "" };
+                        let (format, _) = log_value(&arg.name, arg.ty.as_ref(), quote!());
+                        format!("{}: {}{}", arg.escaped_name(), type_format, format)
+                    }
+                    Inclusion::Skip(arg) => {
+                        // Only arguments name must be printed.
+                        format!("{}: ...", arg.escaped_name())
+                    }
+                }
+            }))
+            .collect::<Vec<_>>()
+            .join("\n\t");
+
+        format!("{}{}{}{}", prefix_enter, typed_name, sep, arguments_format)
+    };
+
+    let traced_arguments = type_names
+        .iter()
+        .cloned()
+        .chain(receiver_values)
+        .chain(
+            fn_arguments
+                .into_iter()
+                .filter_map(|inclusion| match inclusion {
+                    Inclusion::Value(arg) => {
+                        let value = arg.value;
+                        let value = quote! { #value };
+                        let type_name = if types { Some(type_of(&value)) } else { None };
+                        let (_, value) = log_value(&arg.name, arg.ty.as_ref(), value);
+                        Some(type_name.into_iter().chain(Some(value)))
+                    }
+                    Inclusion::Skip(_) => None,
+                })
+                .flatten(),
+        )
+        .collect::<Vec<_>>();
+
+    let return_var = "res";
+    let fn_result = if attrs
+        .iter()
+        .filter(|attr| attr.is_direct())
+        .any(|attr| attr.filter.excludes(return_var))
+    {
+        Inclusion::Skip(return_var)
+    } else {
+        Inclusion::Value(return_var)
+    };
+
+    // The returned iterator, stream or closure is traced on its own.
+    let (mut exiting_format, mut exiting_args) =
+        if matches!(fn_result, Inclusion::Skip(_)) || items.is_some() || closure.is_some() {
+            (
+                format!("{}{}\n\t{}: ...", prefix_exit, typed_name, return_var),
+                type_names,
+            )
+        } else {
+            let value = quote! { __inner_return_value__ };
+            let (type_format, type_name) = if types {
+                ("{} = ", Some(type_of(&value)))
+            } else {
+                ("", None)
+            };
+            let output = match fn_decl.output {
+                syn::ReturnType::Type(_, ref ty) => Some(&**ty),
+                syn::ReturnType::Default => None,
+            };
+            let (format, value) = log_value(return_var, output, value);
+            let format = format!(
+                "{}{}\n\t{}: {}{}",
+                prefix_exit, typed_name, return_var, type_format, format
+            );
+
+            (
+                format,
+                type_names
+                    .into_iter()
+                    .chain(type_name)
+                    .chain(Some(value))
+                    .collect(),
+            )
+        };
+
+    let mut exiting_bindings = Vec::new();
+    if binds_name(return_var) {
+        exiting_bindings.push(quote! { let res = &__inner_return_value__; });
+    }
+
+    // The `&mut` arguments are reborrowed by the body, so that they can be logged after it.
+    let mut rebind_stmts = Vec::new();
+    if let Some((mut_after, ref mut_refs)) = mut_after {
+        for mut_ref in mut_refs {
+            let MutRef {
+                ref name,
+                ref binding,
+                ref original,
+                ref mutability,
+                ref ty,
+            } = *mut_ref;
+            let before = proc_macro2::Ident::new(
+                &format!("__mut_before_{}__", name),
+                proc_macro2::Span::call_site(),
+            );
+
+            let logged = debug_value(krate, quote! { #original });
+
+            // `self` cannot be rebound, the body refers to `__self__` instead.
+            if original != "self" {
+                rebind_stmts.push(quote! { let #original = #binding; });
+            }
+            if mut_after.diff {
+                rebind_stmts.push(quote! {
+                    let #before = if __enabled__ {
+                        ::std::format!("{:#?}", #logged)
+                    } else {
+                        ::std::string::String::new()
+                    };
+                });
+                exiting_format.push_str("\n\t{}");
+                exiting_args.push(quote! {
+                    #krate::runtime::debug_diff(#name, &#before, &::std::format!("{:#?}", #logged))
+                });
+            } else {
+                let (format, value) = log_value(name, ty.as_ref(), quote! { #original });
+                exiting_format.push_str(&format!("\n\t{}: {}", name, format));
+                exiting_args.push(value);
+                if binds_name(name) && original != "self" {
+                    exiting_bindings.push(quote! { let #binding = &*#original; });
+                }
+            }
+            rebind_stmts.push(quote! { let #mutability #binding = &mut *#original; });
+        }
+    }
+
+    // The body refers to the reborrowed `self`.
+    let renamed_block;
+    let original_block = match mut_after {
+        Some((mut_after, _)) if mut_after.receiver => {
+            renamed_block = rename_self(original_block);
+            &renamed_block
+        }
+        _ => original_block,
+    };
+
+    let pause_stmt = if attrs.iter().any(|attr| attr.pause) {
+        quote! {{
+            use std::io::{self, BufRead};
+            let stdin = io::stdin();
+            stdin.lock().lines().next();
+        }}
+    } else {
+        quote!()
+    };
+
+    let printer = quote! { #krate::runtime::log::trace! };
+    let qualified_name = format!("::{}", local_name);
+    let line = quote_spanned!(ident.span()=> line!());
+
+    // A boxed closure built by the body is coerced to the declared type,
+    // so that the signature of the closure is inferred from it.
+    let body_output = match (body_kind, &closure) {
+        (BodyKind::Fn, Some((Returned::Boxed, _))) => {
+            let output = &fn_decl.output;
+            quote! { #output }
+        }
+        _ => quote!(),
+    };
+
+    // The type of the returned value is known before the value is logged,
+    // so that whether it implements `Debug` is told, see `debug_value`.
+    let return_type = match fn_decl.output {
+        // Neither `impl Trait` nor `!` can be named in a `let` statement.
+        syn::ReturnType::Type(_, ref ty)
+            if !contains_impl_trait(ty) && !matches!(**ty, syn::Type::Never(_)) =>
+        {
+            quote! { : #ty }
+        }
+        _ => quote!(),
+    };
+
+    let (block_prefix, block_postfix) = match body_kind {
+        BodyKind::Fn => (quote! { move || #body_output }, quote!()),
+        BodyKind::AsyncFn => (quote! { move || async move }, quote! {.await}),
+        BodyKind::Closure => (quote! { || }, quote!()),
+    };
+
+    let track_compile_filter = track_compile_filter();
+
+    let exit = quote! {
+        if __enabled__ {
+            #(#exiting_bindings)*
+            #printer(#exiting_format, #(#exiting_args),*);
+            #pause_stmt
+        }
+    };
+
+    let exit = match items {
+        None => exit,
+        Some((wrapper, returned, limit)) => {
+            let (item_format, item) = log_value("item", None, quote! { __item__ });
+            let item_format = format!(
+                "{}{}\n\titem #{{}}: {}",
+                prefix_exit, printed_name, item_format
+            );
+            let item_binding = if binds_name("item") {
+                quote! { let item = __item__; }
+            } else {
+                quote!()
+            };
+            let end_format = format!("{}{}\n\titems: {{}}, {{}}", prefix_exit, printed_name);
+
+            let traced = quote! {
+                #krate::runtime::#wrapper::new(
+                    __inner_return_value__,
+                    #limit,
+                    move |__index__, __item__| {
+                        if __enabled__ {
+                            #item_binding
+                            #printer(#item_format, __index__, #item);
+                        }
+                    },
+                    move |__count__, __exhausted__| {
+                        if __enabled__ {
+                            let __end__ = if __exhausted__ { "exhausted" } else { "dropped" };
+                            #printer(#end_format, __count__, __end__);
+                        }
+                    },
+                )
+            };
+            let traced = match returned {
+                Returned::Impl => traced,
+                Returned::Boxed => quote! { ::std::boxed::Box::new(#traced) },
+                Returned::Pinned => quote! { ::std::boxed::Box::pin(#traced) },
+            };
+
+            quote! {
+                #exit
+                let __inner_return_value__ = #traced;
+            }
+        }
+    };
+
+    let exit = match closure {
+        None => exit,
+        Some((returned, signature)) => {
+            let params = (0..signature.inputs.len())
+                .map(|i| {
+                    proc_macro2::Ident::new(&format!("_{}", i), proc_macro2::Span::call_site())
+                })
+                .collect::<Vec<_>>();
+            let (params, types) = (&params, &signature.inputs);
+            let mut closure_decl = syn::FnDecl {
+                fn_token: Default::default(),
+                generics: Default::default(),
+                paren_token: Default::default(),
+                inputs: parse_quote! { #(#params: #types),* },
+                variadic: None,
+                output: signature.output,
+            };
+            let closure_block = parse_quote! {{ __closure__(#(#params),*) }};
+
+            let traced_block = construct_traced_block(
+                attrs,
+                krate,
+                BodyKind::Closure,
+                &returned_local_name,
+                &returned_printed_name,
+                ident,
+                &mut closure_decl,
+                &closure_block,
+            )?;
+
+            let inputs = &closure_decl.inputs;
+            let output = &closure_decl.output;
+            let traced = quote! { move |#inputs| #output #traced_block };
+            let traced = match returned {
+                Returned::Boxed => quote! { ::std::boxed::Box::new(#traced) },
+                Returned::Impl | Returned::Pinned => traced,
+            };
+
+            quote! {
+                #exit
+                #[allow(unused_mut)]
+                let mut __closure__ = __inner_return_value__;
+                let __inner_return_value__ = #traced;
+            }
+        }
+    };
+
+    let exit = match future {
+        None => quote! {
+            #exit
+            __inner_return_value__
+        },
+        Some(Returned::Impl) => quote! {
+            #krate::runtime::TracedFuture::new(__inner_return_value__, move |__inner_return_value__| {
+                #exit
+            })
+        },
+        // `Box<dyn Future>` is a future only if it is `Unpin`, so it is pinned to be polled.
+        Some(Returned::Boxed) => quote! {
+            ::std::boxed::Box::new(#krate::runtime::TracedFuture::new(
+                ::std::boxed::Box::into_pin(__inner_return_value__),
+                move |__inner_return_value__| {
+                    #exit
+                },
+            ))
+        },
+        Some(Returned::Pinned) => quote! {
+            ::std::boxed::Box::pin(#krate::runtime::TracedFuture::new(
+                __inner_return_value__,
+                move |__inner_return_value__| {
+                    #exit
+                },
+            ))
+        },
+    };
+
+    // Bind the patterns of the renamed parameters before the original body.
+    let original_block = if bind_stmts.is_empty() {
+        quote! { #original_block }
+    } else {
+        quote! {{
+            #(#bind_stmts)*
+            #original_block
+        }}
+    };
+
+    Ok(parse_quote! {{
+        #(#sensitive_warnings)*
+        #[allow(unused_imports)]
+        use #krate::runtime::{
+            AddrPointer as _, AddrValue as _, HexBytes as _, HexDebug as _, HexFallback as _,
+            HexInt as _, LenCollection as _, LenDebug as _, LenFallback as _, LogDebug as _,
+            LogFallback as _,
+        };
+        #track_compile_filter
+        let __enabled__ = {
+            static __CALLSITE__: #krate::runtime::Callsite =
+                #krate::runtime::Callsite::new(concat!(module_path!(), #qualified_name), file!(), #line);
+            #krate::runtime::inventory::submit! {
+                #krate::runtime::Registration(&__CALLSITE__)
+            }
+            __CALLSITE__.enter()
+        };
+        if __enabled__ {
+            #printer(#entering_format, #(#traced_arguments,)*);
+            #pause_stmt
+        }
+        #(#rebind_stmts)*
+        let __inner_return_value__ #return_type = {
+            let mut __inner_body__ = #block_prefix #original_block;
+            __inner_body__()#block_postfix
+        };
+        #exit
+    }})
+}
+
+/// Tells whether a type contains `impl Trait`, which cannot be named in a `let` statement.
+fn contains_impl_trait(ty: &syn::Type) -> bool {
+    struct ImplTraitFinder(bool);
+
+    impl VisitMut for ImplTraitFinder {
+        fn visit_type_impl_trait_mut(&mut self, _: &mut syn::TypeImplTrait) {
+            self.0 = true;
+        }
+    }
+
+    let mut finder = ImplTraitFinder(false);
+    finder.visit_type_mut(&mut ty.clone());
+    finder.0
+}
+
+/// Logs a value with `Debug` if its type implements it, or else by the name and the size of its type,
+/// see `trace::runtime::Value`.
+fn debug_value(
+    krate: &proc_macro2::TokenStream,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! { (&#krate::runtime::Value(&#value)).__trace_logged__() }
+}
+
+/// Decides whether an argument is named like a secret, i.e., one of the `_`-separated segments
+/// of its name is in `sensitive`, or else in the defaults, e.g., `password`, `api_key`
+/// or `access_token`, but not `secretary`.
+fn is_sensitive(name: &str, sensitive: Option<&[String]>) -> bool {
+    const DEFAULTS: &[&str] = &[
+        "password",
+        "passwd",
+        "passphrase",
+        "secret",
+        "key",
+        "token",
+        "credential",
+        "credentials",
+    ];
+
+    name.to_lowercase()
+        .split('_')
+        .any(|segment| match sensitive {
+            Some(sensitive) => sensitive.iter().any(|sensitive| sensitive == segment),
+            None => DEFAULTS.contains(&segment),
+        })
+}
+
+/// Logs a value with a built-in format, falling back to `debug_value` for `hex` and `len`
+/// when they do not apply to its type.
+fn builtin_value(
+    krate: &proc_macro2::TokenStream,
+    builtin: Builtin,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match builtin {
+        Builtin::Hex => quote! { (&&&#krate::runtime::Value(&#value)).__trace_hex__() },
+        Builtin::Len => quote! { (&&#krate::runtime::Value(&#value)).__trace_len__() },
+        Builtin::Truncate(limit) => {
+            let debug = debug_value(krate, value);
+            quote! { #krate::runtime::Truncate(#debug, #limit) }
+        }
+        Builtin::Hash => {
+            let debug = debug_value(krate, value);
+            quote! { #krate::runtime::DebugHash(#debug) }
+        }
+        Builtin::Addr => quote! { (&#krate::runtime::Value(&#value)).__trace_addr__() },
+        Builtin::Redact { hashed: false } => quote! { #krate::runtime::Redacted },
+        Builtin::Redact { hashed: true } => {
+            let debug = debug_value(krate, value);
+            quote! { #krate::runtime::RedactedHash(#debug) }
+        }
+    }
+}
+
+/// An argument passed by `&mut`, or `&mut self`, logged again at exit by `mut_after`.
+struct MutRef {
+    name: String,
+    /// The name the body refers to the argument by.
+    binding: proc_macro2::Ident,
+    /// The name the argument is logged by at exit.
+    original: proc_macro2::Ident,
+    /// `mut` of the binding, e.g., `mut a: &mut u8`.
+    mutability: Option<syn::token::Mut>,
+    /// Type of the argument, `None` for `self`.
+    ty: Option<syn::Type>,
+}
+
+/// Finds the traced arguments of type `&mut T`, and `&mut self` if `receiver` is set.
+fn extract_mut_refs(
+    fn_decl: &syn::FnDecl,
+    fn_arguments: &[Inclusion<TracedArg>],
+    receiver: bool,
+) -> Vec<MutRef> {
+    let is_traced = |ident: &proc_macro2::Ident| {
+        fn_arguments.iter().any(|inclusion| match inclusion {
+            Inclusion::Value(arg) => arg.value == *ident,
+            Inclusion::Skip(_) => false,
+        })
+    };
+
+    fn_decl
+        .inputs
+        .iter()
+        .filter_map(|input| match *input {
+            syn::FnArg::SelfRef(syn::ArgSelfRef {
+                mutability: Some(_),
+                ref self_token,
+                ..
+            }) if receiver => Some(MutRef {
+                name: "self".to_string(),
+                binding: proc_macro2::Ident::new("__self__", proc_macro2::Span::call_site()),
+                original: proc_macro2::Ident::new("self", self_token.span),
+                mutability: None,
+                ty: None,
+            }),
+            syn::FnArg::Captured(syn::ArgCaptured {
+                pat:
+                    syn::Pat::Ident(syn::PatIdent {
+                        by_ref: None,
+                        ref mutability,
+                        ref ident,
+                        subpat: None,
+                    }),
+                ty:
+                    ref ty @ syn::Type::Reference(syn::TypeReference {
+                        mutability: Some(_),
+                        ..
+                    }),
+                ..
+            }) if is_traced(ident) => Some(MutRef {
+                name: ident.to_string(),
+                binding: ident.clone(),
+                original: proc_macro2::Ident::new(
+                    &format!("__mut_after_{}__", ident),
+                    proc_macro2::Span::call_site(),
+                ),
+                mutability: *mutability,
+                ty: Some(ty.clone()),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Replaces `self` in a body with the reborrowed `__self__`, except within nested items.
+fn rename_self(block: &syn::Block) -> syn::Block {
+    struct SelfRenamer;
+
+    impl VisitMut for SelfRenamer {
+        fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+            if let syn::Expr::Path(ref expr_path) = *expr {
+                if expr_path.qself.is_none() && expr_path.path.is_ident("self") {
+                    *expr = parse_quote! { __self__ };
+                    return;
+                }
+            }
+            visit_mut::visit_expr_mut(self, expr);
+        }
+
+        fn visit_item_mut(&mut self, _item: &mut syn::Item) {}
+
+        fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+            mac.tts = rename_self_tokens(mac.tts.clone());
+        }
+    }
+
+    /// `self` within the arguments of a macro, e.g., `println!("{:?}", self)`.
+    fn rename_self_tokens(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let mut tokens = tokens.into_iter().peekable();
+        let mut renamed = Vec::new();
+
+        while let Some(token) = tokens.next() {
+            renamed.push(match token {
+                proc_macro2::TokenTree::Ident(ref ident) if ident == "self" => {
+                    // Not a path, e.g., `self::helper()`.
+                    match tokens.peek() {
+                        Some(proc_macro2::TokenTree::Punct(ref punct))
+                            if punct.as_char() == ':' =>
+                        {
+                            token.clone()
+                        }
+                        _ => proc_macro2::Ident::new("__self__", ident.span()).into(),
+                    }
+                }
+                proc_macro2::TokenTree::Group(ref group) => {
+                    let mut renamed_group = proc_macro2::Group::new(
+                        group.delimiter(),
+                        rename_self_tokens(group.stream()),
+                    );
+                    renamed_group.set_span(group.span());
+                    renamed_group.into()
+                }
+                token => token,
+            });
+        }
+
+        renamed.into_iter().collect()
+    }
+
+    let mut block = block.clone();
+    SelfRenamer.visit_block_mut(&mut block);
+    block
+}
+
+/// How a function returns a value of a trait, e.g., a future or an iterator.
+#[derive(Clone, Copy)]
+enum Returned {
+    /// `impl Trait`.
+    Impl,
+    /// `Box<dyn Trait>`.
+    Boxed,
+    /// `Pin<Box<dyn Trait>>` or an alias of it, e.g., `BoxFuture<T>` as expanded by `async-trait`.
+    Pinned,
+}
+
+/// Recognizes a function returning a value of trait `name` by its return type,
+/// `aliases` are the names of pinned boxes of the trait.
+fn returned(output: &syn::ReturnType, name: &str, aliases: &[&str]) -> Option<Returned> {
+    fn has_bound(
+        bounds: &syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>,
+        name: &str,
+    ) -> bool {
+        bounds.iter().any(|bound| match *bound {
+            syn::TypeParamBound::Trait(ref trait_bound) => last_ident_is(&trait_bound.path, name),
+            _ => false,
+        })
+    }
+
+    /// `Box<dyn Trait>`.
+    fn is_boxed(ty: &syn::Type, name: &str) -> bool {
+        match *ty {
+            syn::Type::Path(ref type_path) if last_ident_is(&type_path.path, "Box") => {
+                type_args(&type_path.path).into_iter().any(|ty| match *ty {
+                    syn::Type::TraitObject(ref type_trait_object) => {
+                        has_bound(&type_trait_object.bounds, name)
+                    }
+                    _ => false,
+                })
+            }
+            _ => false,
+        }
+    }
+
+    let ty = match *output {
+        syn::ReturnType::Default => return None,
+        syn::ReturnType::Type(_, ref ty) => &**ty,
+    };
+
+    match *ty {
+        syn::Type::ImplTrait(ref type_impl_trait) if has_bound(&type_impl_trait.bounds, name) => {
+            Some(Returned::Impl)
+        }
+        syn::Type::Path(ref type_path) => {
+            let path = &type_path.path;
+            if aliases.iter().any(|alias| last_ident_is(path, alias)) {
+                Some(Returned::Pinned)
+            } else if is_boxed(ty, name) {
+                Some(Returned::Boxed)
+            } else if last_ident_is(path, "Pin")
+                && type_args(path).into_iter().any(|ty| is_boxed(ty, name))
+            {
+                Some(Returned::Pinned)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A closure returned by a function, `impl Fn(A) -> R` or `Box<dyn FnMut(A) -> R>`,
+/// along with the signature of the closure.
+fn returned_closure(
+    output: &syn::ReturnType,
+) -> Option<(Returned, syn::ParenthesizedGenericArguments)> {
+    let ty = match *output {
+        syn::ReturnType::Default => return None,
+        syn::ReturnType::Type(_, ref ty) => &**ty,
+    };
+
+    let (returned, bounds) = match *ty {
+        syn::Type::ImplTrait(ref type_impl_trait) => (Returned::Impl, &type_impl_trait.bounds),
+        syn::Type::Path(ref type_path) if last_ident_is(&type_path.path, "Box") => {
+            match type_args(&type_path.path).into_iter().next() {
+                Some(syn::Type::TraitObject(ref type_trait_object)) => {
+                    (Returned::Boxed, &type_trait_object.bounds)
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    bounds.iter().find_map(|bound| match *bound {
+        syn::TypeParamBound::Trait(ref trait_bound) => {
+            let segment = trait_bound.path.segments.last()?.into_value();
+            match segment.arguments {
+                syn::PathArguments::Parenthesized(ref signature)
+                    if ["Fn", "FnMut", "FnOnce"]
+                        .iter()
+                        .any(|name| segment.ident == name) =>
+                {
+                    Some((returned, signature.clone()))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+fn last_ident_is(path: &syn::Path, name: &str) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| segment.value().ident == name)
+}
+
+/// The type arguments of the last segment of a path, e.g., `T` of `std::pin::Pin<T>`.
+fn type_args(path: &syn::Path) -> Vec<&syn::Type> {
+    match path
+        .segments
+        .last()
+        .map(|segment| &segment.into_value().arguments)
+    {
+        Some(syn::PathArguments::AngleBracketed(ref args)) => args
+            .args
+            .iter()
+            .filter_map(|arg| match *arg {
+                syn::GenericArgument::Type(ref ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Path to the `trace` crate as it is named by the crate being expanded,
+/// i.e., taking a rename in its `Cargo.toml` into account.
+fn crate_path() -> proc_macro2::TokenStream {
+    let name = proc_macro_crate::crate_name(CRATE_NAME).unwrap_or_else(|_| CRATE_NAME.to_string());
+    let ident = proc_macro2::Ident::new(&name, proc_macro2::Span::call_site());
+
+    quote! { ::#ident }
+}
+
+/// Decides by the compile-time filter whether function `name` enclosed in `scope` is instrumented;
+/// the function is named after the module of its source file, as `module_path!()` names it at run time.
+fn is_compiled_in(scope: &[String], name: &str) -> Result<bool, Vec<syn::Error>> {
+    let krate = env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| "crate".to_string());
+    let file_scope = module::file_scope().unwrap_or_default();
+    let qualified_name = Some(&krate)
+        .into_iter()
+        .chain(&file_scope)
+        .chain(scope)
+        .map(String::as_str)
+        .chain(Some(name))
+        .collect::<Vec<_>>()
+        .join("::");
+
+    filter::Filter::current()
+        .map(|filter| filter.enabled(&qualified_name))
+        .map_err(|error| vec![syn::Error::new(proc_macro2::Span::call_site(), error)])
+}
+
+/// Makes the compiler rebuild the crate when the compile-time filter changes.
+fn track_compile_filter() -> syn::Stmt {
+    let env_var = filter::ENV_VAR;
+
+    parse_quote! {
+        const _: Option<&str> = option_env!(#env_var);
+    }
+}
+
+/// Name under which functions of an `impl` block are reported, e.g., `Foo` for `impl<T> Foo<T>`.
+fn type_name(ty: &syn::Type) -> String {
+    match *ty {
+        syn::Type::Path(ref type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.value().ident.to_string())
+            .unwrap_or_default(),
+        syn::Type::Reference(ref type_reference) => type_name(&type_reference.elem),
+        syn::Type::Paren(ref type_paren) => type_name(&type_paren.elem),
+        syn::Type::Group(ref type_group) => type_name(&type_group.elem),
+        _ => ty
+            .into_token_stream()
+            .to_string()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect(),
+    }
+}
+
+/// The type behind the references of `ty`, along with their number, e.g., `(Uuid, 1)` for `&Uuid`.
+fn referent_type(ty: &syn::Type) -> (&syn::Type, usize) {
+    match *ty {
+        syn::Type::Reference(ref type_reference) => {
+            let (ty, derefs) = referent_type(&type_reference.elem);
+            (ty, derefs + 1)
+        }
+        syn::Type::Paren(ref type_paren) => referent_type(&type_paren.elem),
+        syn::Type::Group(ref type_group) => referent_type(&type_group.elem),
+        _ => (ty, 0),
+    }
+}
+
+/// The type within the parentheses or the invisible groups around `ty`, if any.
+fn ungrouped_type(ty: &syn::Type) -> &syn::Type {
+    match *ty {
+        syn::Type::Paren(ref type_paren) => ungrouped_type(&type_paren.elem),
+        syn::Type::Group(ref type_group) => ungrouped_type(&type_group.elem),
+        _ => ty,
+    }
+}
+
+/// Applies the attributes of an enclosing item, `given`, to its child item named `segment`,
+/// which may carry attributes of its own, `local`.
+fn create_context(
+    given: &[AttrApplication],
+    segment: Option<pattern::Segment>,
+    local: Option<args::Args>,
+) -> Vec<AttrApplication> {
+    let is_annotated = local.is_some();
+
+    // `inherit = false` detaches the item from the attributes of the enclosing items.
+    let given = if local.as_ref().is_some_and(|local| !local.inherit) {
+        &[]
+    } else {
+        given
+    };
+
+    given
+        .iter()
+        .cloned()
+        .map(|attr| attr.descend(segment.clone()))
+        .map(|attr| {
+            // Filters of the enclosing items do not apply to an explicitly annotated item.
+            if is_annotated {
+                attr.without_item_filters()
+            } else {
+                attr
+            }
+        })
+        .chain(local.map(AttrApplication::Directly))
+        .collect::<Vec<_>>()
+}
+
+/// Reports the arguments of `#[trace(...)]` without effect as warnings, see `args::Args::unused`.
+fn unused_warnings(args: &args::Args, on_fn: bool) -> Vec<syn::Stmt> {
+    args.unused(on_fn)
+        .iter()
+        .map(|error| {
+            warning(
+                "__trace_unused_argument__",
+                error.span(),
+                &error.to_string(),
+            )
+        })
+        .collect()
+}
+
+/// A statement warning at `span` with `note`, by the use of a deprecated constant named `name`,
+/// as proc macros cannot emit warnings of their own.
+fn warning(name: &str, span: proc_macro2::Span, note: &str) -> syn::Stmt {
+    let warning = proc_macro2::Ident::new(name, span);
+    parse_quote! {{
+        #[deprecated(note = #note)]
+        #[allow(non_upper_case_globals)]
+        const #warning: () = ();
+        #[allow(clippy::let_unit_value)]
+        let _ = #warning;
+    }}
+}
+
+/// Scopes the `warnings` reported for an item by the lint attributes among its `attrs`,
+/// so that, e.g., `#[allow(deprecated)]` silences them when they are placed outside of the item.
+fn scoped_warnings(warnings: Vec<syn::Stmt>, attrs: &[syn::Attribute]) -> Vec<syn::Stmt> {
+    let lints = attrs
+        .iter()
+        .filter(|attr| {
+            ["allow", "warn", "deny", "forbid", "expect"]
+                .iter()
+                .any(|lint| attr.path.is_ident(lint))
+        })
+        .collect::<Vec<_>>();
+    if warnings.is_empty() || lints.is_empty() {
+        return warnings;
+    }
+
+    vec![parse_quote! {
+        #(#lints)*
+        const _: () = {
+            #(#warnings)*
+        };
+    }]
+}
+
+/// An item holding the `warnings` outside of a function body, if any.
+fn warnings_item(warnings: Vec<syn::Stmt>) -> Option<syn::Item> {
+    if warnings.is_empty() {
+        return None;
+    }
+
+    Some(syn::Item::Verbatim(syn::ItemVerbatim {
+        tts: quote! {
+            const _: () = {
+                #(#warnings)*
+            };
+        },
+    }))
+}
+
+/// Decides whether a function is excluded by the filters of the enclosing items.
+fn is_filtered_out(attrs: &[AttrApplication], is_pub: bool, is_trivial: bool) -> bool {
+    attrs.iter().for_each(|attr| match attr {
+        AttrApplication::Directly(args) => args.note_function(None),
+        AttrApplication::Indirectly(args, path) => args.note_function(Some(path)),
+    });
+
+    attrs.iter().any(|attr| match attr {
+        AttrApplication::Directly(args) => args.off,
+        AttrApplication::Indirectly(args, path) => args.excludes_fn(path, is_pub, is_trivial),
+    })
+}
+
+/// The attributes of an item `#[trace]` applies to.
+fn item_attrs(item: &syn::Item) -> &[syn::Attribute] {
+    match *item {
+        syn::Item::Fn(ref item_fn) => &item_fn.attrs,
+        syn::Item::Mod(ref item_mod) => &item_mod.attrs,
+        syn::Item::Impl(ref item_impl) => &item_impl.attrs,
+        syn::Item::Trait(ref item_trait) => &item_trait.attrs,
+        syn::Item::ForeignMod(ref item_foreign_mod) => &item_foreign_mod.attrs,
+        _ => &[],
+    }
+}
+
+/// An item as a segment of a path matched by `enable` and `disable`;
+/// an `extern` block is transparent.
+fn item_segment(item: &syn::Item) -> Option<pattern::Segment> {
+    let segment = match *item {
+        syn::Item::Fn(ref item_fn) => pattern::Segment::Item(item_fn.ident.to_string()),
+        syn::Item::Mod(ref item_mod) => pattern::Segment::Item(item_mod.ident.to_string()),
+        syn::Item::Trait(ref item_trait) => pattern::Segment::Item(item_trait.ident.to_string()),
+        syn::Item::Impl(ref item_impl) => pattern::Segment::Impl {
+            self_ty: type_name(&item_impl.self_ty),
+            trait_: item_impl.trait_.as_ref().and_then(|(_, path, _)| {
+                path.segments
+                    .last()
+                    .map(|segment| segment.value().ident.to_string())
+            }),
+        },
+        _ => return None,
+    };
+
+    Some(segment)
+}
+
+/// Decides whether a function body is a single trivial expression, e.g., `self.field`
+/// or `&self.items[..]`, as in getters.
+fn is_trivial(block: &syn::Block) -> bool {
+    fn is_trivial_expr(expr: &syn::Expr) -> bool {
+        match *expr {
+            syn::Expr::Lit(_) | syn::Expr::Path(_) => true,
+            syn::Expr::Field(ref expr_field) => is_trivial_expr(&expr_field.base),
+            syn::Expr::Reference(ref expr_reference) => is_trivial_expr(&expr_reference.expr),
+            syn::Expr::Paren(ref expr_paren) => is_trivial_expr(&expr_paren.expr),
+            syn::Expr::Unary(ref expr_unary) => is_trivial_expr(&expr_unary.expr),
+            syn::Expr::Cast(ref expr_cast) => is_trivial_expr(&expr_cast.expr),
+            syn::Expr::Index(ref expr_index) => {
+                is_trivial_expr(&expr_index.expr) && is_trivial_expr(&expr_index.index)
+            }
+            syn::Expr::Range(ref expr_range) => {
+                expr_range.from.iter().all(|from| is_trivial_expr(from))
+                    && expr_range.to.iter().all(|to| is_trivial_expr(to))
+            }
+            // E.g., `self.name.clone()` or `self.items.len()`.
+            syn::Expr::MethodCall(ref expr_method_call) => {
+                expr_method_call.args.is_empty() && is_trivial_expr(&expr_method_call.receiver)
+            }
+            _ => false,
+        }
+    }
+
+    match block.stmts.as_slice() {
+        [syn::Stmt::Expr(ref expr)] => is_trivial_expr(expr),
+        _ => false,
+    }
+}
+
+fn extract_local_attrs(
+    attrs: &mut Vec<syn::Attribute>,
+) -> Result<Option<args::Args>, Vec<syn::Error>> {
+    if attrs.is_empty() {
+        return Ok(None);
+    }
+
+    // Evaluate attached macros.
+    let pos = attrs
+        .iter()
+        .position(|attr| attr.path.segments[0].ident == MACRO_NAME);
+
+    if let Some(pos) = pos {
+        // Another MACRO_NAME is attached.
+
+        let trace_macro = attrs.remove(pos);
+
+        // Either a bare `#[trace]` or `#[trace(...)]`.
+        let parser = |input: syn::parse::ParseStream| -> syn::Result<Vec<args::RawArg>> {
+            if input.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let content;
+            syn::parenthesized!(content in input);
+            args::parse_raw_args(&content)
+        };
+        let raw_local_args = parser.parse2(trace_macro.tts).map_err(|err| vec![err])?;
+        let local_args = args::Args::from_raw_args(raw_local_args)?;
+        Ok(Some(local_args))
+    } else {
+        Ok(None)
+    }
+}
+
+/// An argument as it is traced.
+struct TracedArg {
+    /// Name the argument is reported and selected by `enable` and `disable` under.
+    name: String,
+    /// Variable holding the value of the argument.
+    value: proc_macro2::Ident,
+    /// Type of the argument, unless it is inferred or bound within a struct or an enum.
+    ty: Option<syn::Type>,
+}
+
+impl TracedArg {
+    /// Name to be embedded in a format string.
+    fn escaped_name(&self) -> String {
+        escape_format(&self.name)
+    }
+}
+
+/// Escapes the braces of a text to be embedded in a format string.
+fn escape_format(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Collects the variables bound by the parameters of a function.
+/// A parameter binding nothing, e.g., `_: u8` or `Point { .. }: Point`, is renamed to be traced
+/// as a whole under its pattern, which is then bound by the statements pushed to `bind_stmts`.
+fn extract_args(
+    attrs: &[AttrApplication],
+    fn_decl: &mut syn::FnDecl,
+    bind_stmts: &mut Vec<syn::Stmt>,
+) -> Vec<Inclusion<TracedArg>> {
+    // The type of a binding is followed through tuples, references and arrays of the declared type,
+    // e.g., `a: Secret` in `&(a, _): &(Secret, u8)`, and is unknown within structs and enums.
+    fn collect_bindings(
+        pat: &syn::Pat,
+        ty: Option<&syn::Type>,
+        bindings: &mut Vec<(proc_macro2::Ident, Option<syn::Type>)>,
+    ) {
+        let ty = ty.map(ungrouped_type);
+        match (pat, ty) {
+            (syn::Pat::Ident(pat_ident), _) => {
+                let binding_ty = match (&pat_ident.by_ref, ty) {
+                    (Some(_), Some(ty)) => {
+                        let mutability = &pat_ident.mutability;
+                        Some(parse_quote! { &#mutability #ty })
+                    }
+                    _ => ty.cloned(),
+                };
+                bindings.push((pat_ident.ident.clone(), binding_ty));
+                // E.g., `x @ Some(_)`.
+                if let Some((_, ref subpat)) = pat_ident.subpat {
+                    collect_bindings(subpat, ty, bindings);
+                }
+            }
+            (syn::Pat::Ref(pat_ref), Some(syn::Type::Reference(type_reference))) => {
+                collect_bindings(&pat_ref.pat, Some(&type_reference.elem), bindings)
+            }
+            // A pattern matching a reference without `&` binds references, e.g., `a: &Secret`
+            // in `(a, _): &(Secret, u8)`.
+            (syn::Pat::Tuple(_), Some(syn::Type::Reference(type_reference)))
+            | (syn::Pat::Slice(_), Some(syn::Type::Reference(type_reference))) => {
+                let mut referents = Vec::new();
+                collect_bindings(pat, Some(&type_reference.elem), &mut referents);
+                let mutability = &type_reference.mutability;
+                bindings.extend(
+                    referents
+                        .into_iter()
+                        .map(|(ident, ty)| (ident, ty.map(|ty| parse_quote! { &#mutability #ty }))),
+                );
+            }
+            (syn::Pat::Tuple(pat_tuple), _) => {
+                let elems = match ty {
+                    Some(syn::Type::Tuple(type_tuple)) => {
+                        type_tuple.elems.iter().collect::<Vec<_>>()
+                    }
+                    _ => Vec::new(),
+                };
+                let back_offset = elems.len().saturating_sub(pat_tuple.back.len());
+                pat_tuple
+                    .front
+                    .iter()
+                    .enumerate()
+                    .chain(
+                        pat_tuple
+                            .back
+                            .iter()
+                            .enumerate()
+                            .map(|(index, pat)| (back_offset + index, pat)),
+                    )
+                    .for_each(|(index, pat)| {
+                        collect_bindings(pat, elems.get(index).cloned(), bindings)
+                    });
+            }
+            (syn::Pat::Slice(pat_slice), _) => {
+                let elem = match ty {
+                    Some(syn::Type::Array(type_array)) => Some(&*type_array.elem),
+                    Some(syn::Type::Slice(type_slice)) => Some(&*type_slice.elem),
+                    _ => None,
+                };
+                pat_slice
+                    .front
+                    .iter()
+                    .chain(pat_slice.back.iter())
+                    .for_each(|pat| collect_bindings(pat, elem, bindings));
+                if let Some(ref middle) = pat_slice.middle {
+                    collect_bindings(middle, None, bindings);
+                }
+            }
+            (syn::Pat::Struct(pat_struct), _) => pat_struct
+                .fields
+                .iter()
+                .for_each(|field| collect_bindings(&field.pat, None, bindings)),
+            (syn::Pat::TupleStruct(pat_tuple_struct), _) => pat_tuple_struct
+                .pat
+                .front
+                .iter()
+                .chain(pat_tuple_struct.pat.back.iter())
+                .for_each(|pat| collect_bindings(pat, None, bindings)),
+            (syn::Pat::Box(pat_box), _) => collect_bindings(&pat_box.pat, None, bindings),
+            (syn::Pat::Ref(pat_ref), _) => collect_bindings(&pat_ref.pat, None, bindings),
+            // Wildcards, paths, literals, ranges and macros bind nothing.
+            _ => (),
+        }
+    }
+
+    // Only the arguments of `#[trace]` attached to the function itself select its arguments.
+    let include = |arg: TracedArg| {
+        if attrs
+            .iter()
+            .filter(|attr| attr.is_direct())
+            .any(|attr| attr.filter.excludes(&arg.name))
+        {
+            Inclusion::Skip(arg)
+        } else {
+            Inclusion::Value(arg)
+        }
+    };
+
+    let mut args = Vec::new();
+
+    for (index, input) in fn_decl.inputs.iter_mut().enumerate() {
+        let (pat, ty) = match *input {
+            syn::FnArg::SelfRef(_) | syn::FnArg::SelfValue(_) => continue, // ignore `self`
+            syn::FnArg::Captured(ref mut arg_captured) => {
+                (&mut arg_captured.pat, Some(&arg_captured.ty))
+            }
+            syn::FnArg::Inferred(ref mut pat) => (pat, None),
+            // An unnamed parameter of a trait method, e.g., `fn f(&self, u8);`, is traced as `_`.
+            syn::FnArg::Ignored(ref ty) => {
+                let ty = ty.clone();
+                let value = unnamed_arg(index);
+
+                args.push(include(TracedArg {
+                    name: "_".to_string(),
+                    value: value.clone(),
+                    ty: Some(ty.clone()),
+                }));
+                *input = parse_quote! { #value: #ty };
+                continue;
+            }
+        };
+        let mut bindings = Vec::new();
+        collect_bindings(pat, ty, &mut bindings);
+
+        if bindings.is_empty() {
+            let value = unnamed_arg(index);
+
+            args.push(include(TracedArg {
+                name: pat.into_token_stream().to_string(),
+                value: value.clone(),
+                ty: ty.cloned(),
+            }));
+            bind_stmts.push(parse_quote! { let #pat = #value; });
+            *pat = parse_quote! { #value };
+        } else {
+            // A parameter named by `forward_args` in a wrapper is unnamed in the wrapped function.
+            let unnamed = unnamed_arg(index);
+            args.extend(bindings.into_iter().map(|(ident, ty)| {
+                include(TracedArg {
+                    name: if ident == unnamed {
+                        "_".to_string()
+                    } else {
+                        ident.to_string()
+                    },
+                    value: ident,
+                    ty,
+                })
+            }));
+        }
+    }
+
+    args
+}
+
+/// The name given to the unnamed parameter at `index`, or to one bound by a pattern.
+fn unnamed_arg(index: usize) -> proc_macro2::Ident {
+    proc_macro2::Ident::new(
+        &format!("__arg_{}__", index),
+        proc_macro2::Span::call_site(),
+    )
+}
+
+#[derive(Clone)]
+enum AttrApplication {
+    Directly(args::Args),
+    /// Applied to an enclosing item, the path leads from that item to the current one.
+    Indirectly(args::Args, Vec<pattern::Segment>),
+}
+
+impl AttrApplication {
+    /// Moves the application down to the child item named `segment`, if any.
+    fn descend(self, segment: Option<pattern::Segment>) -> Self {
+        match self {
+            AttrApplication::Directly(args) => {
+                AttrApplication::Indirectly(args, segment.into_iter().collect())
+            }
+            AttrApplication::Indirectly(args, mut path) => {
+                path.extend(segment);
+                AttrApplication::Indirectly(args, path)
+            }
+        }
+    }
+
+    fn without_item_filters(self) -> Self {
+        match self {
+            AttrApplication::Directly(args) => {
+                AttrApplication::Directly(args.without_item_filters())
+            }
+            AttrApplication::Indirectly(args, path) => {
+                // The dropped filters still select the item, which is not to be reported as unused.
+                args.filter.excludes_path(&path);
+                AttrApplication::Indirectly(args.without_item_filters(), path)
+            }
+        }
+    }
+
+    fn is_direct(&self) -> bool {
+        matches!(self, AttrApplication::Directly(_))
+    }
+}
+
+impl Deref for AttrApplication {
+    type Target = args::Args;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            AttrApplication::Directly(attr) => attr,
+            AttrApplication::Indirectly(attr, _) => attr,
+        }
+    }
+}
+
+/// Identifies whether argument value have to be printed or just specified as present,
+/// e.g.,
+/// Inclusion::Value(x) => x: value of x
+/// Inclusion::Skip(x) => x: ...
+enum Inclusion<T> {
+    Value(T),
+    Skip(T),
+}
@@ -0,0 +1,202 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::parse_quote;
+
+/// Reads the body of a module declared as `mod foo;` within the item `#[trace]` is attached to,
+/// `scope` lists the inline modules enclosing the declaration.
+/// Modules declared in the same way within the body are read as well.
+pub(crate) fn load(item_mod: &mut syn::ItemMod, scope: &[String]) -> Result<(), Vec<syn::Error>> {
+    let source_file = proc_macro::Span::call_site().local_file().ok_or_else(|| {
+        vec![syn::Error::new_spanned(
+            &item_mod,
+            "cannot locate the source file declaring the module",
+        )]
+    })?;
+    let source_dir = source_file.parent().unwrap_or_else(|| Path::new(""));
+
+    // Whether the source file is a "mod-rs" file, e.g., `lib.rs`, is unknown,
+    // try the directory of a non-"mod-rs" file, e.g., `src/db/` for `src/db.rs`, first.
+    let mut bases = Vec::new();
+    if let Some(stem) = source_file.file_stem() {
+        if !["lib", "main", "mod"].iter().any(|name| stem == *name) {
+            bases.push(source_dir.join(stem));
+        }
+    }
+    bases.push(source_dir.to_path_buf());
+
+    let mut tried = Vec::new();
+    for base in bases {
+        let dir = scope.iter().fold(base, |dir, m| dir.join(m));
+        // A `#[path]` outside of inline modules is relative to the directory of the source file.
+        let path_base = if scope.is_empty() {
+            source_dir.to_path_buf()
+        } else {
+            dir.clone()
+        };
+
+        match resolve(item_mod, &dir, &path_base) {
+            Ok(_) => return load_in(item_mod, &dir, &path_base),
+            Err(candidates) => tried.push(candidates),
+        }
+    }
+
+    Err(vec![syn::Error::new_spanned(
+        &item_mod,
+        format!(
+            "file not found for module `{}`, tried: {}",
+            item_mod.ident,
+            tried.join(", ")
+        ),
+    )])
+}
+
+/// The module path of the source file the macro is invoked in, e.g., `["db", "query"]` for `src/db/query.rs`,
+/// following the layout of cargo targets; `None` if the file is not located or lies outside of the layout.
+pub(crate) fn file_scope() -> Option<Vec<String>> {
+    let source_file = fs::canonicalize(proc_macro::Span::call_site().local_file()?).ok()?;
+    let manifest_dir = fs::canonicalize(env::var_os("CARGO_MANIFEST_DIR")?).ok()?;
+    let mut components = source_file
+        .strip_prefix(manifest_dir)
+        .ok()?
+        .iter()
+        .map(|component| component.to_str())
+        .collect::<Option<Vec<_>>>()?;
+    let stem = components.pop()?.strip_suffix(".rs")?;
+
+    // The directories below the one of the crate root, e.g., `src/` or `examples/<name>/`.
+    let dirs = match components.as_slice() {
+        ["src", "bin"] | ["examples"] | ["tests"] | ["benches"] => return Some(Vec::new()),
+        ["src", "bin", _, dirs @ ..]
+        | ["examples", _, dirs @ ..]
+        | ["tests", _, dirs @ ..]
+        | ["benches", _, dirs @ ..]
+        | ["src", dirs @ ..] => dirs,
+        _ => return None,
+    };
+
+    let mut scope = dirs.iter().map(|dir| dir.to_string()).collect::<Vec<_>>();
+    let is_root = dirs.is_empty() && (stem == "lib" || stem == "main");
+    if !is_root && stem != "mod" {
+        scope.push(stem.to_string());
+    }
+
+    Some(scope)
+}
+
+/// Finds the file of module `item_mod` declared in a module whose child modules reside in `dir`;
+/// `path_base` is the directory `#[path]` is relative to.
+/// Returns the tried paths on failure.
+fn resolve(item_mod: &syn::ItemMod, dir: &Path, path_base: &Path) -> Result<PathBuf, String> {
+    let candidates = match path_attr(item_mod) {
+        Some(path) => vec![path_base.join(path)],
+        None => {
+            let name = item_mod.ident.to_string();
+            vec![
+                dir.join(format!("{}.rs", name)),
+                dir.join(name).join("mod.rs"),
+            ]
+        }
+    };
+
+    candidates
+        .iter()
+        .find(|candidate| candidate.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            candidates
+                .iter()
+                .map(|candidate| format!("`{}`", candidate.display()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+}
+
+fn load_in(
+    item_mod: &mut syn::ItemMod,
+    dir: &Path,
+    path_base: &Path,
+) -> Result<(), Vec<syn::Error>> {
+    let file = resolve(item_mod, dir, path_base).map_err(|tried| {
+        vec![syn::Error::new_spanned(
+            &item_mod,
+            format!(
+                "file not found for module `{}`, tried: {}",
+                item_mod.ident, tried
+            ),
+        )]
+    })?;
+
+    let content = fs::read_to_string(&file).map_err(|err| {
+        vec![syn::Error::new_spanned(
+            &item_mod,
+            format!("cannot read `{}`: {}", file.display(), err),
+        )]
+    })?;
+    let parsed = syn::parse_file(&content).map_err(|err| {
+        vec![syn::Error::new_spanned(
+            &item_mod,
+            format!("cannot parse `{}`: {}", file.display(), err),
+        )]
+    })?;
+
+    // Modules declared in a "mod-rs" file or in a file given by `#[path]` reside next to it.
+    let child_dir =
+        if path_attr(item_mod).is_some() || file.file_name().is_some_and(|name| name == "mod.rs") {
+            file.parent().unwrap_or_else(|| Path::new("")).to_path_buf()
+        } else {
+            dir.join(item_mod.ident.to_string())
+        };
+    let file_dir = file.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+
+    let mut items = parsed.items;
+    load_nested(&mut items, &child_dir, &file_dir)?;
+
+    // Makes the compiler rebuild the crate when the file changes.
+    let path = fs::canonicalize(&file)
+        .unwrap_or(file)
+        .display()
+        .to_string();
+    items.insert(0, parse_quote! { const _: &[u8] = include_bytes!(#path); });
+
+    item_mod.attrs.retain(|attr| !attr.path.is_ident("path"));
+    item_mod.attrs.extend(parsed.attrs);
+    item_mod.content = Some((syn::token::Brace::default(), items));
+    item_mod.semi = None;
+
+    Ok(())
+}
+
+fn load_nested(
+    items: &mut [syn::Item],
+    dir: &Path,
+    path_base: &Path,
+) -> Result<(), Vec<syn::Error>> {
+    for item in items.iter_mut() {
+        if let syn::Item::Mod(ref mut item_mod) = *item {
+            if item_mod.content.is_none() {
+                load_in(item_mod, dir, path_base)?;
+            } else if let Some((_, ref mut items)) = item_mod.content {
+                let dir = dir.join(item_mod.ident.to_string());
+                load_nested(items, &dir, &dir)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The value of `#[path = "..."]`, if any.
+fn path_attr(item_mod: &syn::ItemMod) -> Option<String> {
+    item_mod
+        .attrs
+        .iter()
+        .find_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                ref ident,
+                lit: syn::Lit::Str(ref lit_str),
+                ..
+            })) if ident == "path" => Some(lit_str.value()),
+            _ => None,
+        })
+}
@@ -0,0 +1,210 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Matches `text` against `pattern` where `*` stands for any sequence of characters
+/// and `?` for any single character.
+pub(crate) fn glob(pattern: &str, text: &str) -> bool {
+    let (pattern, text) = (pattern.as_bytes(), text.as_bytes());
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and of the text it was matched against.
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Matches `name` or any of its `::`-delimited prefixes against `pattern`,
+/// so that a pattern selecting a module or a type also selects everything nested in it.
+pub(crate) fn glob_path(pattern: &str, name: &str) -> bool {
+    glob(pattern, name)
+        || name
+            .match_indices("::")
+            .any(|(pos, _)| glob(pattern, &name[..pos]))
+}
+
+/// An entry of `enable`, `disable` or `skip_trait_impls`.
+#[derive(Clone)]
+pub(crate) struct Pattern {
+    kind: Kind,
+    /// The entry as written, for diagnostics.
+    source: String,
+    span: proc_macro2::Span,
+    /// Whether the pattern has matched anything, shared between the copies
+    /// handed down to the enclosed items.
+    used: Rc<Cell<bool>>,
+}
+
+#[derive(Clone)]
+enum Kind {
+    /// A meta word, e.g., `enable(foo)`, matching the name exactly.
+    Ident(String),
+    /// A string, e.g., `enable("get_*")` or `enable("Foo::bar")`, matched as a glob.
+    Glob(String),
+    /// A string enclosed in slashes, e.g., `enable("/^try_/")`, matched as a regular expression.
+    Regex(regex::Regex),
+    /// A string `impl <Type>` or `impl <Trait> for <Type>` with globs on either side,
+    /// e.g., `disable("impl Debug for *")`, selecting `impl` blocks.
+    Impl {
+        trait_: Option<String>,
+        self_ty: String,
+    },
+}
+
+/// A segment of the path of an item relative to the item an attribute is attached to.
+#[derive(Clone)]
+pub(crate) enum Segment {
+    /// A function or a module.
+    Item(String),
+    /// An `impl` block for `self_ty`, possibly of `trait_`.
+    Impl {
+        self_ty: String,
+        trait_: Option<String>,
+    },
+}
+
+impl Segment {
+    pub(crate) fn name(&self) -> &str {
+        match *self {
+            Segment::Item(ref name) => name,
+            Segment::Impl { ref self_ty, .. } => self_ty,
+        }
+    }
+}
+
+impl Pattern {
+    /// A meta word entry.
+    pub(crate) fn ident(ident: &proc_macro2::Ident) -> Self {
+        Self::new(
+            Kind::Ident(ident.to_string()),
+            ident.to_string(),
+            ident.span(),
+        )
+    }
+
+    /// Parses a string entry; a regular expression must be enclosed in slashes.
+    pub(crate) fn parse(pattern: &str, span: proc_macro2::Span) -> Result<Self, String> {
+        let source = pattern.to_string();
+        let kind = if let Some(pattern) = pattern.strip_prefix("impl ") {
+            let pattern = pattern.trim();
+            let (trait_, self_ty) = match pattern.find(" for ") {
+                Some(pos) => (
+                    Some(pattern[..pos].trim().to_string()),
+                    pattern[pos + " for ".len()..].trim().to_string(),
+                ),
+                None => (None, pattern.to_string()),
+            };
+
+            if self_ty.is_empty() || trait_.as_ref().is_some_and(String::is_empty) {
+                Err("expected `impl <Type>` or `impl <Trait> for <Type>`".to_string())
+            } else {
+                Ok(Kind::Impl { trait_, self_ty })
+            }
+        } else if pattern.len() >= 2 && pattern.starts_with('/') && pattern.ends_with('/') {
+            regex::Regex::new(&pattern[1..pattern.len() - 1])
+                .map(Kind::Regex)
+                .map_err(|err| format!("invalid regular expression: {}", err))
+        } else if pattern.is_empty() {
+            Err("empty pattern".to_string())
+        } else {
+            Ok(Kind::Glob(pattern.to_string()))
+        }?;
+
+        Ok(Self::new(kind, source, span))
+    }
+
+    fn new(kind: Kind, source: String, span: proc_macro2::Span) -> Self {
+        Self {
+            kind,
+            source,
+            span,
+            used: Rc::new(Cell::new(false)),
+        }
+    }
+
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub(crate) fn span(&self) -> proc_macro2::Span {
+        self.span
+    }
+
+    /// Whether the pattern, or any of its copies, has matched a name or a path.
+    pub(crate) fn is_used(&self) -> bool {
+        self.used.get()
+    }
+
+    /// Matches a single name, e.g., of an argument.
+    pub(crate) fn matches(&self, name: &str) -> bool {
+        let matches = match self.kind {
+            Kind::Ident(ref ident) => ident == name,
+            Kind::Glob(ref pattern) => glob(pattern, name),
+            Kind::Regex(ref regex) => regex.is_match(name),
+            Kind::Impl { .. } => false,
+        };
+
+        self.mark(matches)
+    }
+
+    /// Matches a path of an item relative to the item the pattern is attached to,
+    /// e.g., `["Foo", "bar"]` for method `bar` of `impl Foo` in a traced `mod`.
+    /// A pattern matching a prefix of the path selects everything below as well.
+    pub(crate) fn matches_path(&self, path: &[Segment]) -> bool {
+        match self.kind {
+            Kind::Impl {
+                ref trait_,
+                ref self_ty,
+            } => {
+                path.iter().any(|segment| match *segment {
+                    Segment::Impl {
+                        self_ty: ref segment_self_ty,
+                        trait_: ref segment_trait,
+                    } => {
+                        glob(self_ty, segment_self_ty)
+                            && match (trait_, segment_trait) {
+                                (None, _) => true,
+                                (Some(trait_), Some(segment_trait)) => glob(trait_, segment_trait),
+                                (Some(_), None) => false,
+                            }
+                    }
+                    Segment::Item(_) => false,
+                }) && self.mark(true)
+            }
+            _ => (1..=path.len()).any(|len| {
+                let prefix = path[..len]
+                    .iter()
+                    .map(Segment::name)
+                    .collect::<Vec<_>>()
+                    .join("::");
+                self.matches(&prefix)
+            }),
+        }
+    }
+
+    fn mark(&self, matches: bool) -> bool {
+        if matches {
+            self.used.set(true);
+        }
+        matches
+    }
+}